@@ -1,27 +1,76 @@
 use crate::error::ContractError;
-use crate::msg::{ExecuteMsg, InstantiateMsg, QueryMsg};
+use crate::msg::{
+    AuctionPriceResponse, CreatorBalanceResponse, CurrentPhaseResponse, DenomBalance,
+    EscrowResponse, ExecuteMsg, FeeSource, InstantiateMsg, MintAllowanceResponse,
+    MintPercentResponse, OraclePriceResponse, OracleQueryMsg, PlatformFeesResponse,
+    PriceScheduleResponse, QueryMsg, ReceiveMsg, ReferralEarningsResponse,
+    SubscriptionStatusResponse, TipsResponse,
+};
 use crate::state::{
-    increment_reply_id, increment_token_index, CollectionInfo, CollectionParams, Config,
-    MintParams, PendingInstantiation, UpdateMintFeeParams, WithdrawParams, CONFIG,
-    CREATOR_COLLECTIONS, CW721_REPLY_ID, PENDING_INSTANTIATIONS, TOKEN_INDEX,
+    denom_from_key, denom_key, increment_escrow_id, increment_reply_id, increment_token_index,
+    CollectionInfo, CollectionParams, Config, Coupon, CouponParams, Discount, DutchAuction, Escrow,
+    GiftMintParams, MintParams, Payout, PendingInstantiation, Price, PriceOracle, PricingMode,
+    QuantityTier, RemoveCouponParams, RenewParams, RevenueSplit, SalePhase, SaleProceeds,
+    SchedulePricesParams, ScheduledPrice, SetCollectionFeeParams, SetCreatorFeeParams,
+    Subscription, UpdateMaxSupplyParams, UpdateMerkleRootParams, UpdateMintFeeParams,
+    UpdatePhasesParams, UpdateReferralRateParams, UpdateRevenueSplitParams, UsdPrice,
+    WithdrawParams, COLLECTION_FEE_OVERRIDES, COLLECTION_TIPS, CONFIG, COUPONS, CREATOR_BALANCES,
+    CREATOR_COLLECTIONS, CREATOR_FEE_OVERRIDES, CREATOR_TIPS, CW721_REPLY_ID, ESCROWS,
+    ESCROW_EXPIRIES, PENDING_INSTANTIATIONS, PHASE_WALLET_MINTS, PLATFORM_FEES, REFERRAL_EARNINGS,
+    REFUNDED_TOKENS, SUBSCRIBER_EXPIRIES, SUBSCRIBER_TOKENS, SUBSCRIPTIONS, TOKEN_ESCROWS,
+    TOKEN_INDEX, WALLET_MINTS,
 };
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
 use cosmwasm_std::{
-    to_json_binary, Addr, BankMsg, Binary, Coin, CosmosMsg, Deps, DepsMut, Empty, Env, MessageInfo,
-    Reply, Response, StdResult, SubMsg, Uint128, WasmMsg,
+    from_json, to_json_binary, Addr, BankMsg, Binary, Coin, CosmosMsg, Decimal, Deps, DepsMut,
+    Empty, Env, MessageInfo, Order, Reply, Response, StdError, StdResult, Storage, SubMsg,
+    Timestamp, Uint128, WasmMsg,
 };
 use cw2::set_contract_version;
+use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg, Denom};
 use cw721_base::Extension;
 use cw721_non_transferable::{
     ExecuteMsg as Cw721ExecuteMsg, InstantiateMsg as cw721NonTransferableInstantiateMsg,
 };
+use cw_storage_plus::{Bound, Map};
 use cw_utils::parse_reply_instantiate_data;
+use sha2::{Digest, Sha256};
 
 // version info for migration info
 const CONTRACT_NAME: &str = "crates.io:mintyplex";
 const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
 
+/// Highest platform fee the owner can ever configure
+pub const MAX_MINT_PERCENT: Decimal = Decimal::percent(20);
+
+/// Most entries a collection price schedule can have
+pub const MAX_SCHEDULED_PRICES: usize = 20;
+
+/// Most quantity tiers a collection can have
+pub const MAX_QUANTITY_TIERS: usize = 10;
+
+/// Most copies that can be minted in a single transaction
+pub const MAX_MINT_QUANTITY: u32 = 50;
+
+/// Most recipients a single gift mint can have
+pub const MAX_GIFT_RECIPIENTS: usize = 50;
+
+/// Longest refund window a collection can have
+pub const MAX_REFUND_WINDOW: u64 = 30 * 24 * 60 * 60;
+
+/// Most escrowed sales released at once, keeps claims within the gas limit
+pub const MAX_ESCROW_RELEASES: usize = 30;
+
+/// Longest memo a tip can carry
+pub const MAX_TIP_MEMO_LENGTH: usize = 256;
+
+/// Most sale phases a collection can have
+pub const MAX_SALE_PHASES: usize = 10;
+
+/// Most payees a revenue split can have, keeps the per-sale payout loop bounded
+pub const MAX_PAYEES: usize = 20;
+
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn instantiate(
     deps: DepsMut,
@@ -31,6 +80,16 @@ pub fn instantiate(
 ) -> Result<Response, ContractError> {
     set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
 
+    if msg.denom.is_empty() {
+        return Err(ContractError::InvalidInput {});
+    }
+
+    validate_mint_percent(msg.mint_percent)?;
+
+    if let Some(price_oracle) = &msg.price_oracle {
+        validate_price_oracle(deps.as_ref(), price_oracle)?;
+    }
+
     let owner = msg
         .owner
         .and_then(|addr_string| deps.api.addr_validate(addr_string.as_str()).ok())
@@ -39,13 +98,18 @@ pub fn instantiate(
     let config = Config {
         owner: owner.clone(),
         mint_percent: msg.mint_percent,
+        denom: msg.denom.clone(),
+        price_oracle: msg.price_oracle,
+        charge_tip_fee: msg.charge_tip_fee,
+        paused: false,
     };
     CONFIG.save(deps.storage, &config)?;
 
     Ok(Response::new()
         .add_attribute("method", "instantiate")
         .add_attribute("owner", owner)
-        .add_attribute("mint percent", msg.mint_percent.to_string()))
+        .add_attribute("mint percent", msg.mint_percent.to_string())
+        .add_attribute("denom", msg.denom))
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
@@ -58,9 +122,48 @@ pub fn execute(
     match msg {
         ExecuteMsg::CreateCollection(params) => execute_create_collection(deps, env, info, params),
         ExecuteMsg::MintNFT(params) => execute_mint_nft(deps, env, info, params),
+        ExecuteMsg::GiftMint(params) => execute_gift_mint(deps, env, info, params),
+        ExecuteMsg::Renew(params) => execute_renew(deps, env, info, params),
         ExecuteMsg::Withdraw(params) => execute_withdraw(deps, env, info, params),
         ExecuteMsg::UpdateConfig(config) => execute_update_config(deps, env, info, config),
+        ExecuteMsg::SetCreatorFee(params) => execute_set_creator_fee(deps, env, info, params),
+        ExecuteMsg::SetCollectionFee(params) => execute_set_collection_fee(deps, env, info, params),
         ExecuteMsg::UpdateMintFee(params) => execute_update_mint_fee(deps, env, info, params),
+        ExecuteMsg::SchedulePrices(params) => execute_schedule_prices(deps, env, info, params),
+        ExecuteMsg::UpdatePhases(params) => execute_update_phases(deps, env, info, params),
+        ExecuteMsg::UpdateMerkleRoot(params) => execute_update_merkle_root(deps, env, info, params),
+        ExecuteMsg::UpdateMaxSupply(params) => execute_update_max_supply(deps, env, info, params),
+        ExecuteMsg::PausePlatform {} => execute_set_platform_paused(deps, env, info, true),
+        ExecuteMsg::ResumePlatform {} => execute_set_platform_paused(deps, env, info, false),
+        ExecuteMsg::PauseCollection { collection_name } => {
+            execute_set_collection_paused(deps, env, info, collection_name, true)
+        }
+        ExecuteMsg::ResumeCollection { collection_name } => {
+            execute_set_collection_paused(deps, env, info, collection_name, false)
+        }
+        ExecuteMsg::CloseCollection { collection_name } => {
+            execute_close_collection(deps, env, info, collection_name)
+        }
+        ExecuteMsg::UpdateRevenueSplit(params) => {
+            execute_update_revenue_split(deps, env, info, params)
+        }
+        ExecuteMsg::UpdateReferralRate(params) => {
+            execute_update_referral_rate(deps, env, info, params)
+        }
+        ExecuteMsg::RegisterCoupon(params) => execute_register_coupon(deps, env, info, params),
+        ExecuteMsg::RemoveCoupon(params) => execute_remove_coupon(deps, env, info, params),
+        ExecuteMsg::Receive(msg) => execute_receive(deps, env, info, msg),
+        ExecuteMsg::ClaimEarnings { denoms } => execute_claim_earnings(deps, env, info, denoms),
+        ExecuteMsg::RequestRefund {
+            collection_address,
+            token_id,
+        } => execute_request_refund(deps, env, info, collection_address, token_id),
+        ExecuteMsg::Tip {
+            creator,
+            collection_name,
+            memo,
+        } => execute_tip(deps, env, info, creator, collection_name, memo),
+        ExecuteMsg::ReleaseEscrow { creator } => execute_release_escrow(deps, env, info, creator),
     }
 }
 
@@ -70,10 +173,54 @@ pub fn execute_create_collection(
     info: MessageInfo,
     params: CollectionParams,
 ) -> Result<Response, ContractError> {
+    if CONFIG.load(deps.storage)?.paused {
+        return Err(ContractError::PlatformPaused {});
+    }
+
     if params.name.is_empty() || params.symbol.is_empty() {
         return Err(ContractError::InvalidInput {});
     }
 
+    let pricing_mode = params.pricing_mode.unwrap_or_default();
+    match &pricing_mode {
+        PricingMode::Fixed => validate_prices(deps.as_ref(), &params.prices)?,
+        PricingMode::DutchAuction(auction) => validate_auction(deps.as_ref(), auction)?,
+        PricingMode::Usd(usd_price) => validate_usd_price(deps.as_ref(), usd_price)?,
+    }
+
+    let phases = params.phases.unwrap_or_default();
+    validate_sale_phases(deps.as_ref(), &pricing_mode, &phases)?;
+
+    let quantity_tiers = params.quantity_tiers.unwrap_or_default();
+    validate_quantity_tiers(&quantity_tiers)?;
+
+    if let Some(revenue_split) = &params.revenue_split {
+        validate_revenue_split(deps.as_ref(), revenue_split)?;
+    }
+
+    let referral_rate = params.referral_rate.unwrap_or_default();
+    validate_referral_rate(referral_rate)?;
+
+    if let Some(refund_window) = params.refund_window {
+        validate_refund_window(refund_window)?;
+    }
+
+    if params.subscription_period == Some(0) {
+        return Err(ContractError::InvalidSubscriptionPeriod {});
+    }
+
+    if params.max_supply == Some(0) {
+        return Err(ContractError::InvalidMaxSupply {});
+    }
+
+    if params.max_per_wallet == Some(0) {
+        return Err(ContractError::InvalidInput {});
+    }
+
+    if let Some(merkle_root) = &params.merkle_root {
+        validate_merkle_root(merkle_root)?;
+    }
+
     let reply_id = increment_reply_id(deps.storage)?;
 
     let pending = PendingInstantiation {
@@ -86,7 +233,23 @@ pub fn execute_create_collection(
     let collection_info = CollectionInfo {
         name: params.name.clone(),
         symbol: params.symbol.clone(),
-        mint_fee: params.mint_fee,
+        prices: params.prices,
+        price_schedule: vec![],
+        pricing_mode,
+        phases,
+        quantity_tiers,
+        revenue_split: params.revenue_split,
+        referral_rate,
+        gift_allowance: params.gift_allowance,
+        gifted: 0,
+        max_supply: params.max_supply,
+        announced_supply: params.max_supply,
+        minted: 0,
+        max_per_wallet: params.max_per_wallet,
+        merkle_root: params.merkle_root,
+        refund_window: params.refund_window,
+        subscription_period: params.subscription_period,
+        paused: false,
         collection_address: None,
     };
 
@@ -99,8 +262,10 @@ pub fn execute_create_collection(
     let wasm_msg = WasmMsg::Instantiate {
         admin: None,
         code_id: params.code_id,
+        // the factory administers its collections so it can hold, transfer and burn the
+        // tokens of sales still in their refund window
         msg: to_json_binary(&cw721NonTransferableInstantiateMsg {
-            admin: None,
+            admin: Some(env.contract.address.to_string()),
             name: params.name.clone(),
             symbol: params.symbol.clone(),
             minter: env.contract.address.to_string(),
@@ -123,155 +288,2457 @@ pub fn execute_mint_nft(
     info: MessageInfo,
     params: MintParams,
 ) -> Result<Response, ContractError> {
-    let mint_fee = CREATOR_COLLECTIONS
-        .load(
+    let coin = single_coin(&info.funds)?;
+
+    let denom = Denom::Native(coin.denom.clone());
+    let amount = coin.amount;
+
+    mint_nft(deps, env, info.sender, denom, amount, params)
+}
+
+/// The shopper picks the payment denom by what they send, so exactly one coin is expected
+fn single_coin(funds: &[Coin]) -> Result<&Coin, ContractError> {
+    match funds {
+        [] => Err(ContractError::NoFunds {}),
+        [coin] => Ok(coin),
+        _ => Err(ContractError::MultipleDenoms {}),
+    }
+}
+
+pub fn execute_receive(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    wrapper: Cw20ReceiveMsg,
+) -> Result<Response, ContractError> {
+    let sender = deps.api.addr_validate(&wrapper.sender)?;
+
+    match from_json(&wrapper.msg)? {
+        ReceiveMsg::MintNFT(params) => {
+            execute_mint_nft_cw20(deps, env, info.sender, sender, wrapper.amount, params)
+        }
+        ReceiveMsg::Tip {
+            creator,
+            collection_name,
+            memo,
+        } => tip(
+            deps,
+            sender,
+            Denom::Cw20(info.sender),
+            wrapper.amount,
+            creator,
+            collection_name,
+            memo,
+        ),
+        ReceiveMsg::Renew(params) => renew(
+            deps,
+            env,
+            sender,
+            Denom::Cw20(info.sender),
+            wrapper.amount,
+            params,
+        ),
+    }
+}
+
+/// Mints a product paid for with CW20 tokens. `token` is the CW20 contract that
+/// forwarded the payment and `sender` is the shopper who sent it.
+pub fn execute_mint_nft_cw20(
+    deps: DepsMut,
+    env: Env,
+    token: Addr,
+    sender: Addr,
+    amount: Uint128,
+    params: MintParams,
+) -> Result<Response, ContractError> {
+    mint_nft(deps, env, sender, Denom::Cw20(token), amount, params)
+}
+
+/// Mints `quantity` copies of a product to `buyer` for a payment of `amount` in `denom`
+/// that this contract has already received. The creator share is credited to their
+/// earnings balance, or held in escrow while the collection refund window is open.
+fn mint_nft(
+    deps: DepsMut,
+    env: Env,
+    buyer: Addr,
+    denom: Denom,
+    amount: Uint128,
+    params: MintParams,
+) -> Result<Response, ContractError> {
+    let mut collection_info = CREATOR_COLLECTIONS.load(
+        deps.storage,
+        (&params.collection_creator, &params.collection_name),
+    )?;
+
+    // the factory mints into any of its collections, so the address must be the one of
+    // the collection whose rules and price apply
+    let collection_address = collection_info
+        .collection_address
+        .clone()
+        .ok_or(ContractError::PendingCollectionNotFound {})?;
+    if collection_address != params.collection_address {
+        return Err(ContractError::CollectionAddressMismatch {});
+    }
+
+    check_sales_open(deps.storage, &collection_info)?;
+
+    let referrer = params
+        .referrer
+        .as_ref()
+        .map(|referrer| deps.api.addr_validate(referrer.as_str()))
+        .transpose()?;
+
+    if referrer.as_ref() == Some(&buyer) {
+        return Err(ContractError::InvalidReferrer {});
+    }
+
+    let quantity = params.quantity.unwrap_or(1);
+    if quantity == 0 || quantity > MAX_MINT_QUANTITY {
+        return Err(ContractError::InvalidQuantity {
+            max: MAX_MINT_QUANTITY,
+        });
+    }
+
+    record_mints(&mut collection_info, quantity.into())?;
+    let purchased = record_purchases(
+        deps.storage,
+        &collection_info,
+        &params.collection_creator,
+        &buyer,
+        quantity,
+    )?;
+
+    // The allowlist of the active phase wins over the collection one, allocations cap the
+    // purchases of the same scope
+    let mut allowlist = collection_info
+        .merkle_root
+        .as_deref()
+        .map(|merkle_root| (merkle_root, purchased));
+
+    if !collection_info.phases.is_empty() {
+        let phase = active_phase(&collection_info, env.block.time)
+            .ok_or(ContractError::SaleNotActive {})?;
+        let phase_purchased = record_phase_purchases(
             deps.storage,
-            (&params.collection_creator, &params.collection_name),
-        )?
-        .mint_fee;
+            &params.collection_creator,
+            &params.collection_name,
+            phase,
+            &buyer,
+            quantity,
+        )?;
 
-    if info
-        .funds
-        .iter()
-        .any(|coin| coin.denom != "uxion" && coin.amount.u128() != mint_fee)
-    {
-        return Err(ContractError::IncorrectFunds {});
+        if let Some(merkle_root) = &phase.merkle_root {
+            allowlist = Some((merkle_root, phase_purchased));
+        }
     }
 
-    // Create mint msg
-    let mint_msg = Cw721ExecuteMsg::<Extension, Empty>::Mint {
-        token_id: increment_token_index(deps.storage)?.to_string(),
-        owner: info.sender.to_string(),
-        token_uri: Some(params.token_uri),
-        extension: None,
+    if let Some((merkle_root, purchased)) = allowlist {
+        verify_allowlist(merkle_root, &buyer, &params, purchased, quantity)?;
+    }
+    CREATOR_COLLECTIONS.save(
+        deps.storage,
+        (&params.collection_creator, &params.collection_name),
+        &collection_info,
+    )?;
+
+    let unit_price = current_mint_fee(deps.as_ref(), &env, &collection_info, &denom)?;
+    let unit_price = apply_quantity_tier(&collection_info.quantity_tiers, quantity, unit_price)?;
+    let mint_fee = unit_price.checked_mul(Uint128::from(quantity))?;
+
+    let mint_fee = match &params.coupon {
+        Some(code) => redeem_coupon(
+            deps.storage,
+            &env,
+            &params,
+            code,
+            &denom,
+            quantity,
+            mint_fee,
+        )?,
+        None => mint_fee,
     };
-    let msg = CosmosMsg::Wasm(WasmMsg::Execute {
-        contract_addr: params.collection_address.to_string(),
-        msg: to_json_binary(&mint_msg)?,
-        funds: vec![],
-    });
 
-    let mint_percent = CONFIG.load(deps.storage)?.mint_percent;
+    if let Some(max_payment) = params.max_payment {
+        if mint_fee > max_payment {
+            return Err(ContractError::MaxPaymentExceeded {
+                max_payment,
+                price: mint_fee,
+            });
+        }
+    }
+
+    let refund = check_payment(&denom, mint_fee, amount)?;
 
-    let mintyplex_amount = (mint_fee * mint_percent) / 100;
-    let creator_amount = mint_fee - mintyplex_amount;
+    let token_ids = (0..quantity)
+        .map(|_| Ok(increment_token_index(deps.storage)?.to_string()))
+        .collect::<StdResult<Vec<_>>>()?;
 
-    let mintyplex_bank_msg = BankMsg::Send {
-        to_address: env.contract.address.to_string(),
-        amount: vec![Coin {
-            denom: "uxion".to_string(),
-            amount: Uint128::from(mintyplex_amount),
-        }],
+    // tokens of a refundable sale stay with the factory until the refund window closes,
+    // so a refund can burn them
+    let token_owner = match collection_info.refund_window {
+        Some(_) => &env.contract.address,
+        None => &buyer,
     };
+    let msgs = token_ids
+        .iter()
+        .map(|token_id| {
+            mint_nft_msg(
+                &collection_address,
+                token_id,
+                &params.token_uri,
+                token_owner,
+            )
+        })
+        .collect::<StdResult<Vec<_>>>()?;
 
-    let creator_bank_msg = BankMsg::Send {
-        to_address: params.collection_creator.to_string(),
-        amount: vec![Coin {
-            denom: "uxion".to_string(),
-            amount: Uint128::from(creator_amount),
-        }],
+    let (mintyplex_amount, mut creator_amount) = split_mint_fee(
+        deps.storage,
+        &params.collection_creator,
+        &params.collection_name,
+        mint_fee,
+    )?;
+
+    let mut response = Response::new();
+
+    // the referral commission comes out of the creator share
+    let referral = match referrer {
+        Some(referrer) => {
+            let commission = creator_amount.checked_mul_floor(collection_info.referral_rate)?;
+            creator_amount = creator_amount.checked_sub(commission)?;
+
+            response = response
+                .add_attribute("referrer", &referrer)
+                .add_attribute("referral_commission", commission);
+
+            Some(Payout {
+                address: referrer,
+                amount: commission,
+            })
+        }
+        None => None,
     };
 
-    Ok(Response::new()
-        .add_message(msg)
-        .add_message(mintyplex_bank_msg)
-        .add_message(creator_bank_msg)
-        .add_attribute("action", "mint nft"))
+    let proceeds = SaleProceeds {
+        denom: denom.clone(),
+        platform_amount: mintyplex_amount,
+        referral,
+        payouts: split_creator_amount(
+            collection_info.revenue_split.as_ref(),
+            &params.collection_creator,
+            creator_amount,
+        )?,
+    };
+
+    if let Some(subscription_period) = collection_info.subscription_period {
+        let expires = env.block.time.plus_seconds(subscription_period);
+        for token_id in &token_ids {
+            start_subscription(deps.storage, &collection_address, token_id, &buyer, expires)?;
+        }
+
+        response = response.add_attribute("expires", expires.to_string());
+    }
+
+    match collection_info.refund_window {
+        Some(refund_window) => {
+            let refundable_until = env.block.time.plus_seconds(refund_window);
+
+            let escrow = Escrow {
+                buyer: buyer.clone(),
+                collection_address,
+                token_ids,
+                amount: mint_fee,
+                proceeds,
+                refundable_until,
+            };
+            escrow_sale(deps.storage, &params.collection_creator, &escrow)?;
+
+            response = response.add_attribute("refundable_until", refundable_until.to_string());
+        }
+        None => credit_sale_proceeds(deps.storage, &proceeds)?,
+    }
+
+    response = response
+        .add_messages(msgs)
+        .add_attribute("action", "mint nft")
+        .add_attribute("quantity", quantity.to_string())
+        .add_attribute("unit_price", unit_price)
+        .add_attribute("denom", denom_name(&denom))
+        .add_attribute("mintyplex_amount", mintyplex_amount)
+        .add_attribute("creator_amount", creator_amount);
+
+    if !refund.is_zero() {
+        response = response
+            .add_message(payment_msg(&denom, &buyer, refund)?)
+            .add_attribute("refund", refund);
+    }
+
+    Ok(response)
 }
 
-pub fn execute_withdraw(
+/// Mints a product from the sender's collection to each recipient free of charge
+pub fn execute_gift_mint(
     deps: DepsMut,
-    _env: Env,
+    env: Env,
     info: MessageInfo,
-    params: WithdrawParams,
+    params: GiftMintParams,
 ) -> Result<Response, ContractError> {
-    let config = CONFIG.load(deps.storage)?;
+    let mut collection_info =
+        CREATOR_COLLECTIONS.load(deps.storage, (&info.sender, &params.collection_name))?;
 
-    if info.sender != config.owner {
-        return Err(ContractError::Unauthorized {});
+    if params.recipients.is_empty()
+        || params.recipients.len() > MAX_GIFT_RECIPIENTS
+        || params.token_uri.is_empty()
+    {
+        return Err(ContractError::InvalidInput {});
     }
 
-    let bank_msg = BankMsg::Send {
-        to_address: params.withdraw_address.to_string(),
-        amount: vec![Coin {
-            denom: "uxion".to_string(),
-            amount: Uint128::from(params.withdraw_amount),
-        }],
-    };
+    let collection_address = collection_info
+        .collection_address
+        .clone()
+        .ok_or(ContractError::PendingCollectionNotFound {})?;
+
+    let gifts = params.recipients.len() as u32;
+    if let Some(gift_allowance) = collection_info.gift_allowance {
+        let remaining = gift_allowance.saturating_sub(collection_info.gifted);
+        if gifts > remaining {
+            return Err(ContractError::GiftAllowanceExceeded { remaining });
+        }
+    }
+
+    // gifted subscriptions come with one period of access
+    let expires = collection_info
+        .subscription_period
+        .map(|subscription_period| env.block.time.plus_seconds(subscription_period));
+
+    let msgs = params
+        .recipients
+        .iter()
+        .map(|recipient| {
+            let recipient = deps.api.addr_validate(recipient)?;
+            let token_id = increment_token_index(deps.storage)?.to_string();
+            if let Some(expires) = expires {
+                start_subscription(
+                    deps.storage,
+                    &collection_address,
+                    &token_id,
+                    &recipient,
+                    expires,
+                )?;
+            }
+            Ok(mint_nft_msg(
+                &collection_address,
+                &token_id,
+                &params.token_uri,
+                &recipient,
+            )?)
+        })
+        .collect::<Result<Vec<_>, ContractError>>()?;
+
+    record_mints(&mut collection_info, gifts.into())?;
+    collection_info.gifted += gifts;
+    CREATOR_COLLECTIONS.save(
+        deps.storage,
+        (&info.sender, &params.collection_name),
+        &collection_info,
+    )?;
 
     Ok(Response::new()
-        .add_message(bank_msg)
-        .add_attribute("action", "withdraw"))
+        .add_messages(msgs)
+        .add_attribute("action", "gift mint")
+        .add_attribute("collection_name", params.collection_name)
+        .add_attribute("gifts", gifts.to_string()))
 }
 
-pub fn execute_update_config(
+pub fn execute_renew(
     deps: DepsMut,
-    _env: Env,
+    env: Env,
     info: MessageInfo,
-    new_config: Config,
+    params: RenewParams,
 ) -> Result<Response, ContractError> {
-    let config = CONFIG.load(deps.storage)?;
+    let coin = single_coin(&info.funds)?;
 
-    if info.sender != config.owner {
-        return Err(ContractError::Unauthorized {});
+    let denom = Denom::Native(coin.denom.clone());
+    let amount = coin.amount;
+
+    renew(deps, env, info.sender, denom, amount, params)
+}
+
+/// Fails while the platform or the collection is paused
+fn check_sales_open(
+    storage: &dyn Storage,
+    collection_info: &CollectionInfo,
+) -> Result<(), ContractError> {
+    if CONFIG.load(storage)?.paused {
+        return Err(ContractError::PlatformPaused {});
     }
 
-    CONFIG.save(deps.storage, &new_config)?;
+    if collection_info.paused {
+        return Err(ContractError::CollectionPaused {});
+    }
 
-    Ok(Response::new().add_attribute("action", "update config"))
+    Ok(())
 }
 
-pub fn execute_update_mint_fee(
+/// Extends a subscription token by one period for a payment of `amount` in `denom` that
+/// this contract has already received. Anyone can pay for a renewal.
+fn renew(
     deps: DepsMut,
-    _env: Env,
-    info: MessageInfo,
-    params: UpdateMintFeeParams,
+    env: Env,
+    payer: Addr,
+    denom: Denom,
+    amount: Uint128,
+    params: RenewParams,
 ) -> Result<Response, ContractError> {
-    let mut collection_info =
-        CREATOR_COLLECTIONS.load(deps.storage, (&info.sender, &params.collection_name))?;
+    let collection_info = CREATOR_COLLECTIONS.load(
+        deps.storage,
+        (&params.collection_creator, &params.collection_name),
+    )?;
 
-    collection_info.mint_fee = params.mint_fee;
+    check_sales_open(deps.storage, &collection_info)?;
 
-    CREATOR_COLLECTIONS.save(
+    let subscription_period = collection_info
+        .subscription_period
+        .ok_or(ContractError::SubscriptionNotFound {})?;
+    let collection_address = collection_info
+        .collection_address
+        .clone()
+        .ok_or(ContractError::PendingCollectionNotFound {})?;
+
+    let subscription = SUBSCRIPTIONS
+        .may_load(deps.storage, (&collection_address, &params.token_id))?
+        .ok_or(ContractError::SubscriptionNotFound {})?;
+
+    let renewal_fee = current_mint_fee(deps.as_ref(), &env, &collection_info, &denom)?;
+    let refund = check_payment(&denom, renewal_fee, amount)?;
+
+    // a lapsed subscription restarts from now
+    let expires = subscription
+        .expires
+        .max(env.block.time)
+        .plus_seconds(subscription_period);
+    start_subscription(
         deps.storage,
-        (&info.sender, &params.collection_name),
-        &collection_info,
+        &collection_address,
+        &params.token_id,
+        &subscription.owner,
+        expires,
     )?;
 
-    Ok(Response::new().add_attribute("action", "update mint fee"))
-}
+    let (mintyplex_amount, creator_amount) = split_mint_fee(
+        deps.storage,
+        &params.collection_creator,
+        &params.collection_name,
+        renewal_fee,
+    )?;
+    credit_sale_proceeds(
+        deps.storage,
+        &SaleProceeds {
+            denom: denom.clone(),
+            platform_amount: mintyplex_amount,
+            referral: None,
+            payouts: split_creator_amount(
+                collection_info.revenue_split.as_ref(),
+                &params.collection_creator,
+                creator_amount,
+            )?,
+        },
+    )?;
 
-#[cfg_attr(not(feature = "library"), entry_point)]
-pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
-    match msg {
-        QueryMsg::Config {} => to_json_binary(&query_config(deps)?),
-        QueryMsg::TokenIndex {} => to_json_binary(&query_token_index(deps)?),
-        QueryMsg::CreatorCollections {
-            creator,
-            collection_name,
-        } => to_json_binary(&query_creator_collections(deps, creator, collection_name)?),
+    let mut response = Response::new()
+        .add_attribute("action", "renew")
+        .add_attribute("token_id", params.token_id)
+        .add_attribute("expires", expires.to_string())
+        .add_attribute("denom", denom_name(&denom))
+        .add_attribute("mintyplex_amount", mintyplex_amount)
+        .add_attribute("creator_amount", creator_amount);
+
+    if !refund.is_zero() {
+        response = response
+            .add_message(payment_msg(&denom, &payer, refund)?)
+            .add_attribute("refund", refund);
     }
+
+    Ok(response)
 }
 
-fn query_config(deps: Deps) -> StdResult<Config> {
-    let config = CONFIG.load(deps.storage)?;
-    Ok(config)
+/// Sets the expiry of a subscription token and keeps the owner's latest expiry current
+fn start_subscription(
+    storage: &mut dyn Storage,
+    collection_address: &Addr,
+    token_id: &str,
+    owner: &Addr,
+    expires: Timestamp,
+) -> Result<(), ContractError> {
+    let subscription = Subscription {
+        owner: owner.clone(),
+        expires,
+    };
+    SUBSCRIPTIONS.save(storage, (collection_address, token_id), &subscription)?;
+    SUBSCRIBER_TOKENS.save(storage, (collection_address, owner, token_id), &expires)?;
+
+    SUBSCRIBER_EXPIRIES.update(
+        storage,
+        (collection_address, owner),
+        |latest| -> StdResult<_> { Ok(latest.map_or(expires, |latest| latest.max(expires))) },
+    )?;
+
+    Ok(())
 }
 
-fn query_token_index(deps: Deps) -> StdResult<u64> {
-    let index = TOKEN_INDEX.load(deps.storage)?;
-    Ok(index)
+/// Ends the subscription of a refunded token and works out the owner's latest expiry from
+/// the tokens they have left
+fn end_subscription(
+    storage: &mut dyn Storage,
+    collection_address: &Addr,
+    token_id: &str,
+) -> StdResult<()> {
+    let Some(subscription) = SUBSCRIPTIONS.may_load(storage, (collection_address, token_id))?
+    else {
+        return Ok(());
+    };
+    let owner = &subscription.owner;
+
+    SUBSCRIPTIONS.remove(storage, (collection_address, token_id));
+    SUBSCRIBER_TOKENS.remove(storage, (collection_address, owner, token_id));
+
+    let latest = SUBSCRIBER_TOKENS
+        .prefix((collection_address, owner))
+        .range(storage, None, None, Order::Ascending)
+        .map(|item| item.map(|(_, expires)| expires))
+        .collect::<StdResult<Vec<_>>>()?
+        .into_iter()
+        .max();
+
+    match latest {
+        Some(latest) => SUBSCRIBER_EXPIRIES.save(storage, (collection_address, owner), &latest)?,
+        None => SUBSCRIBER_EXPIRIES.remove(storage, (collection_address, owner)),
+    }
+
+    Ok(())
 }
 
-fn query_creator_collections(
-    deps: Deps,
-    creator: Addr,
+pub fn execute_tip(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    creator: String,
     collection_name: String,
-) -> StdResult<CollectionInfo> {
-    let collection_info = CREATOR_COLLECTIONS
-        .may_load(deps.storage, (&creator, &collection_name))?
-        .unwrap_or_default();
-    Ok(collection_info)
+    memo: Option<String>,
+) -> Result<Response, ContractError> {
+    let coin = single_coin(&info.funds)?;
+
+    let denom = Denom::Native(coin.denom.clone());
+    let amount = coin.amount;
+
+    tip(
+        deps,
+        info.sender,
+        denom,
+        amount,
+        creator,
+        collection_name,
+        memo,
+    )
+}
+
+/// Credits a tip of `amount` in `denom` that this contract has already received to the
+/// creator, less the platform cut when tips are charged
+fn tip(
+    deps: DepsMut,
+    fan: Addr,
+    denom: Denom,
+    amount: Uint128,
+    creator: String,
+    collection_name: String,
+    memo: Option<String>,
+) -> Result<Response, ContractError> {
+    let creator = deps.api.addr_validate(&creator)?;
+
+    // tips go to existing collections only
+    let collection_info = CREATOR_COLLECTIONS.load(deps.storage, (&creator, &collection_name))?;
+
+    // anyone can send a CW20 receive hook, only tokens the collection sells for are trusted
+    if matches!(denom, Denom::Cw20(_)) && !accepts_denom(&collection_info, &denom) {
+        return Err(ContractError::UnacceptedDenom {
+            denom: denom_name(&denom),
+        });
+    }
+
+    if amount.is_zero() {
+        return Err(ContractError::NoFunds {});
+    }
+
+    if memo
+        .as_ref()
+        .is_some_and(|memo| memo.len() > MAX_TIP_MEMO_LENGTH)
+    {
+        return Err(ContractError::InvalidInput {});
+    }
+
+    let (mintyplex_amount, creator_amount) = if CONFIG.load(deps.storage)?.charge_tip_fee {
+        split_mint_fee(deps.storage, &creator, &collection_name, amount)?
+    } else {
+        (Uint128::zero(), amount)
+    };
+
+    accrue_platform_fees(deps.storage, &denom, mintyplex_amount)?;
+    credit_creator_balance(deps.storage, &creator, &denom, creator_amount)?;
+
+    let key = denom_key(&denom);
+    CREATOR_TIPS.update(
+        deps.storage,
+        (&creator, &key),
+        |tips| -> Result<_, ContractError> { Ok(tips.unwrap_or_default().checked_add(amount)?) },
+    )?;
+    COLLECTION_TIPS.update(
+        deps.storage,
+        (&creator, &collection_name, &key),
+        |tips| -> Result<_, ContractError> { Ok(tips.unwrap_or_default().checked_add(amount)?) },
+    )?;
+
+    let mut response = Response::new()
+        .add_attribute("action", "tip")
+        .add_attribute("fan", fan)
+        .add_attribute("creator", creator)
+        .add_attribute("collection_name", collection_name)
+        .add_attribute("denom", denom_name(&denom))
+        .add_attribute("mintyplex_amount", mintyplex_amount)
+        .add_attribute("creator_amount", creator_amount);
+
+    if let Some(memo) = memo {
+        response = response.add_attribute("memo", memo);
+    }
+
+    Ok(response)
+}
+
+pub fn execute_claim_earnings(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    denoms: Option<Vec<Denom>>,
+) -> Result<Response, ContractError> {
+    let released = release_escrows(deps.storage, &info.sender, env.block.time)?;
+
+    let balances = match denoms {
+        Some(denoms) => {
+            // each balance is paid out once however often its denom is listed
+            let mut keys = denoms.iter().map(denom_key).collect::<Vec<_>>();
+            keys.sort();
+            keys.dedup();
+
+            keys.into_iter()
+                .map(|key| {
+                    let balance = CREATOR_BALANCES.may_load(deps.storage, (&info.sender, &key))?;
+                    Ok(balance.map(|balance| (key, balance)))
+                })
+                .filter_map(StdResult::transpose)
+                .collect::<StdResult<Vec<_>>>()?
+        }
+        None => CREATOR_BALANCES
+            .prefix(&info.sender)
+            .range(deps.storage, None, None, Order::Ascending)
+            .collect::<StdResult<Vec<_>>>()?,
+    };
+
+    for (key, _) in &balances {
+        CREATOR_BALANCES.remove(deps.storage, (&info.sender, key));
+    }
+
+    let messages = payout_msgs(&info.sender, &balances)?;
+
+    if messages.is_empty() {
+        return Err(ContractError::NothingToClaim {});
+    }
+
+    Ok(Response::new()
+        .add_messages(messages)
+        .add_messages(delivery_msgs(&released)?)
+        .add_attribute("action", "claim earnings")
+        .add_attribute("creator", info.sender))
+}
+
+pub fn execute_request_refund(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    collection_address: String,
+    token_id: String,
+) -> Result<Response, ContractError> {
+    let collection_address = deps.api.addr_validate(&collection_address)?;
+
+    let (creator, escrow_id) = TOKEN_ESCROWS
+        .may_load(deps.storage, (&collection_address, &token_id))?
+        .ok_or(ContractError::RefundNotAvailable {})?;
+    let escrow = ESCROWS.load(deps.storage, (&creator, escrow_id))?;
+
+    if info.sender != escrow.buyer {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    if env.block.time >= escrow.refundable_until {
+        return Err(ContractError::RefundWindowClosed {});
+    }
+
+    ESCROWS.remove(deps.storage, (&creator, escrow_id));
+    ESCROW_EXPIRIES.remove(
+        deps.storage,
+        (&creator, escrow.refundable_until.nanos(), escrow_id),
+    );
+    for token_id in &escrow.token_ids {
+        TOKEN_ESCROWS.remove(deps.storage, (&collection_address, token_id));
+        end_subscription(deps.storage, &collection_address, token_id)?;
+        REFUNDED_TOKENS.save(
+            deps.storage,
+            (&collection_address, token_id),
+            &env.block.time,
+        )?;
+    }
+
+    // the factory still holds the tokens of the sale
+    let burn_msgs = escrow
+        .token_ids
+        .iter()
+        .map(|token_id| {
+            cw721_msg(
+                &collection_address,
+                &Cw721ExecuteMsg::Burn {
+                    token_id: token_id.clone(),
+                },
+            )
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+
+    let mut response = Response::new()
+        .add_messages(burn_msgs)
+        .add_attribute("action", "request refund")
+        .add_attribute("token_ids", escrow.token_ids.join(","))
+        .add_attribute("amount", escrow.amount);
+
+    // sales a coupon made free have nothing to pay back
+    if !escrow.amount.is_zero() {
+        response = response.add_message(payment_msg(
+            &escrow.proceeds.denom,
+            &escrow.buyer,
+            escrow.amount,
+        )?);
+    }
+
+    Ok(response)
+}
+
+pub fn execute_release_escrow(
+    deps: DepsMut,
+    env: Env,
+    _info: MessageInfo,
+    creator: String,
+) -> Result<Response, ContractError> {
+    let creator = deps.api.addr_validate(&creator)?;
+
+    let released = release_escrows(deps.storage, &creator, env.block.time)?;
+
+    Ok(Response::new()
+        .add_messages(delivery_msgs(&released)?)
+        .add_attribute("action", "release escrow")
+        .add_attribute("creator", creator)
+        .add_attribute("released", released.len().to_string()))
+}
+
+fn escrow_sale(
+    storage: &mut dyn Storage,
+    creator: &Addr,
+    escrow: &Escrow,
+) -> Result<(), ContractError> {
+    let escrow_id = increment_escrow_id(storage)?;
+
+    ESCROWS.save(storage, (creator, escrow_id), escrow)?;
+    ESCROW_EXPIRIES.save(
+        storage,
+        (creator, escrow.refundable_until.nanos(), escrow_id),
+        &Empty {},
+    )?;
+    for token_id in &escrow.token_ids {
+        TOKEN_ESCROWS.save(
+            storage,
+            (&escrow.collection_address, token_id),
+            &(creator.clone(), escrow_id),
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Credits up to `MAX_ESCROW_RELEASES` of a creator's escrowed sales whose refund window
+/// has closed and returns the released sales, whose tokens still have to be delivered
+fn release_escrows(
+    storage: &mut dyn Storage,
+    creator: &Addr,
+    time: Timestamp,
+) -> Result<Vec<Escrow>, ContractError> {
+    // only walks the escrows that have matured, oldest first
+    let released = ESCROW_EXPIRIES
+        .sub_prefix(creator)
+        .keys(
+            storage,
+            None,
+            Some(Bound::inclusive((time.nanos(), u64::MAX))),
+            Order::Ascending,
+        )
+        .take(MAX_ESCROW_RELEASES)
+        .collect::<StdResult<Vec<_>>>()?;
+
+    let mut escrows = Vec::with_capacity(released.len());
+    for (refundable_until, escrow_id) in released {
+        let escrow = ESCROWS.load(storage, (creator, escrow_id))?;
+        ESCROWS.remove(storage, (creator, escrow_id));
+        ESCROW_EXPIRIES.remove(storage, (creator, refundable_until, escrow_id));
+        for token_id in &escrow.token_ids {
+            TOKEN_ESCROWS.remove(storage, (&escrow.collection_address, token_id));
+        }
+
+        credit_sale_proceeds(storage, &escrow.proceeds)?;
+        escrows.push(escrow);
+    }
+
+    Ok(escrows)
+}
+
+/// Hands the tokens of released sales over from the factory to their buyers
+fn delivery_msgs(escrows: &[Escrow]) -> StdResult<Vec<CosmosMsg>> {
+    escrows
+        .iter()
+        .flat_map(|escrow| {
+            escrow.token_ids.iter().map(|token_id| {
+                cw721_msg(
+                    &escrow.collection_address,
+                    &Cw721ExecuteMsg::TransferNft {
+                        recipient: escrow.buyer.to_string(),
+                        token_id: token_id.clone(),
+                    },
+                )
+            })
+        })
+        .collect()
+}
+
+/// Credits the platform fees, referral commission and creator payouts of a final sale
+fn credit_sale_proceeds(
+    storage: &mut dyn Storage,
+    proceeds: &SaleProceeds,
+) -> Result<(), ContractError> {
+    accrue_platform_fees(storage, &proceeds.denom, proceeds.platform_amount)?;
+
+    if let Some(referral) = &proceeds.referral {
+        credit_referral_commission(storage, &referral.address, &proceeds.denom, referral.amount)?;
+    }
+
+    for payout in &proceeds.payouts {
+        credit_creator_balance(storage, &payout.address, &proceeds.denom, payout.amount)?;
+    }
+
+    Ok(())
+}
+
+fn accrue_platform_fees(
+    storage: &mut dyn Storage,
+    denom: &Denom,
+    amount: Uint128,
+) -> Result<(), ContractError> {
+    if amount.is_zero() {
+        return Ok(());
+    }
+
+    PLATFORM_FEES.update(
+        storage,
+        &denom_key(denom),
+        |platform_fees| -> Result<_, ContractError> {
+            let mut platform_fees = platform_fees.unwrap_or_default();
+            platform_fees.accrued = platform_fees.accrued.checked_add(amount)?;
+            Ok(platform_fees)
+        },
+    )?;
+
+    Ok(())
+}
+
+/// Applies a coupon to the mint fee and records the redemption
+fn redeem_coupon(
+    storage: &mut dyn Storage,
+    env: &Env,
+    params: &MintParams,
+    code: &str,
+    denom: &Denom,
+    quantity: u32,
+    mint_fee: Uint128,
+) -> Result<Uint128, ContractError> {
+    let code_hash = hex::encode(Sha256::digest(code.as_bytes()));
+    let key = (
+        &params.collection_creator,
+        params.collection_name.as_str(),
+        code_hash.as_str(),
+    );
+
+    let mut coupon = COUPONS
+        .may_load(storage, key)?
+        .ok_or(ContractError::CouponNotFound {})?;
+
+    if env.block.time >= coupon.expires {
+        return Err(ContractError::CouponExpired {});
+    }
+
+    // every copy bought with the coupon counts as a redemption
+    if coupon.max_redemptions.saturating_sub(coupon.redemptions) < quantity {
+        return Err(ContractError::CouponExhausted {});
+    }
+
+    let discount = match &coupon.discount {
+        Discount::Percent(percent) => mint_fee.checked_mul_floor(*percent)?,
+        Discount::Fixed {
+            denom: coupon_denom,
+            amount,
+        } => {
+            if coupon_denom != denom {
+                return Err(ContractError::CouponNotApplicable {
+                    denom: denom_name(denom),
+                });
+            }
+            amount.checked_mul(Uint128::from(quantity))?
+        }
+    };
+
+    coupon.redemptions += quantity;
+    COUPONS.save(storage, key, &coupon)?;
+
+    Ok(mint_fee.saturating_sub(discount))
+}
+
+/// Credits a referral commission to the referrer's claimable balance and their
+/// lifetime referral earnings
+fn credit_referral_commission(
+    storage: &mut dyn Storage,
+    referrer: &Addr,
+    denom: &Denom,
+    commission: Uint128,
+) -> Result<(), ContractError> {
+    if commission.is_zero() {
+        return Ok(());
+    }
+
+    credit_creator_balance(storage, referrer, denom, commission)?;
+
+    REFERRAL_EARNINGS.update(
+        storage,
+        (referrer, &denom_key(denom)),
+        |earnings| -> Result<_, ContractError> {
+            Ok(earnings.unwrap_or_default().checked_add(commission)?)
+        },
+    )?;
+
+    Ok(())
+}
+
+fn credit_creator_balance(
+    storage: &mut dyn Storage,
+    creator: &Addr,
+    denom: &Denom,
+    amount: Uint128,
+) -> Result<(), ContractError> {
+    if amount.is_zero() {
+        return Ok(());
+    }
+
+    CREATOR_BALANCES.update(
+        storage,
+        (creator, &denom_key(denom)),
+        |balance| -> Result<_, ContractError> {
+            Ok(balance.unwrap_or_default().checked_add(amount)?)
+        },
+    )?;
+
+    Ok(())
+}
+
+/// Builds the cw721 mint message for a token
+fn mint_nft_msg(
+    collection_address: &Addr,
+    token_id: &str,
+    token_uri: &str,
+    owner: &Addr,
+) -> StdResult<CosmosMsg> {
+    let mint_msg = Cw721ExecuteMsg::Mint {
+        token_id: token_id.to_string(),
+        owner: owner.to_string(),
+        token_uri: Some(token_uri.to_string()),
+        extension: None,
+    };
+
+    cw721_msg(collection_address, &mint_msg)
+}
+
+fn cw721_msg(
+    collection_address: &Addr,
+    msg: &Cw721ExecuteMsg<Extension, Empty>,
+) -> StdResult<CosmosMsg> {
+    Ok(CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: collection_address.to_string(),
+        msg: to_json_binary(msg)?,
+        funds: vec![],
+    }))
+}
+
+/// Splits a mint fee into the (platform, creator) shares.
+///
+/// The platform share is rounded down and the creator receives the remainder, so any
+/// rounding dust goes to the creator and both shares always add up to the mint fee.
+fn split_mint_fee(
+    storage: &dyn Storage,
+    creator: &Addr,
+    collection_name: &str,
+    mint_fee: Uint128,
+) -> Result<(Uint128, Uint128), ContractError> {
+    let (mint_percent, _) = mint_percent(storage, creator, collection_name)?;
+
+    let mintyplex_amount = mint_fee.checked_mul_floor(mint_percent)?;
+    let creator_amount = mint_fee.checked_sub(mintyplex_amount)?;
+
+    Ok((mintyplex_amount, creator_amount))
+}
+
+/// Returns the platform fee of a collection, the most specific override wins
+fn mint_percent(
+    storage: &dyn Storage,
+    creator: &Addr,
+    collection_name: &str,
+) -> StdResult<(Decimal, FeeSource)> {
+    if let Some(mint_percent) =
+        COLLECTION_FEE_OVERRIDES.may_load(storage, (creator, collection_name))?
+    {
+        return Ok((mint_percent, FeeSource::Collection));
+    }
+
+    if let Some(mint_percent) = CREATOR_FEE_OVERRIDES.may_load(storage, creator)? {
+        return Ok((mint_percent, FeeSource::Creator));
+    }
+
+    Ok((CONFIG.load(storage)?.mint_percent, FeeSource::Config))
+}
+
+fn validate_referral_rate(referral_rate: Decimal) -> Result<(), ContractError> {
+    if referral_rate > Decimal::one() {
+        return Err(ContractError::InvalidReferralRate {});
+    }
+
+    Ok(())
+}
+
+fn validate_refund_window(refund_window: u64) -> Result<(), ContractError> {
+    if refund_window == 0 || refund_window > MAX_REFUND_WINDOW {
+        return Err(ContractError::InvalidRefundWindow {
+            max: MAX_REFUND_WINDOW,
+        });
+    }
+
+    Ok(())
+}
+
+fn validate_mint_percent(mint_percent: Decimal) -> Result<(), ContractError> {
+    if mint_percent > MAX_MINT_PERCENT {
+        return Err(ContractError::MintPercentTooHigh {
+            max: MAX_MINT_PERCENT,
+        });
+    }
+
+    Ok(())
+}
+
+/// Divides the creator share of a sale among the revenue split payees by weight. Each
+/// share is rounded down and the rounding dust goes to the primary payee.
+fn split_creator_amount(
+    revenue_split: Option<&RevenueSplit>,
+    creator: &Addr,
+    creator_amount: Uint128,
+) -> Result<Vec<Payout>, ContractError> {
+    let Some(revenue_split) = revenue_split else {
+        return Ok(vec![Payout {
+            address: creator.clone(),
+            amount: creator_amount,
+        }]);
+    };
+
+    let total_weight: u64 = revenue_split.payees.iter().map(|payee| payee.weight).sum();
+
+    let mut payouts = revenue_split
+        .payees
+        .iter()
+        .map(|payee| {
+            let amount = creator_amount.checked_multiply_ratio(payee.weight, total_weight)?;
+            Ok(Payout {
+                address: payee.address.clone(),
+                amount,
+            })
+        })
+        .collect::<Result<Vec<_>, ContractError>>()?;
+
+    let paid = payouts.iter().try_fold(Uint128::zero(), |total, payout| {
+        total.checked_add(payout.amount)
+    })?;
+    let dust = creator_amount.checked_sub(paid)?;
+
+    if let Some(payout) = payouts
+        .iter_mut()
+        .find(|payout| payout.address == revenue_split.primary)
+    {
+        payout.amount = payout.amount.checked_add(dust)?;
+    }
+
+    Ok(payouts)
+}
+
+/// Every scheduled price needs a valid price list and must end after it starts
+fn validate_price_schedule(
+    deps: Deps,
+    price_schedule: &[ScheduledPrice],
+) -> Result<(), ContractError> {
+    if price_schedule.len() > MAX_SCHEDULED_PRICES {
+        return Err(ContractError::InvalidPriceSchedule {});
+    }
+
+    for scheduled in price_schedule {
+        if scheduled
+            .end_time
+            .is_some_and(|end| end <= scheduled.start_time)
+        {
+            return Err(ContractError::InvalidPriceSchedule {});
+        }
+
+        validate_prices(deps, &scheduled.prices)?;
+    }
+
+    Ok(())
+}
+
+/// A revenue split needs between one and `MAX_PAYEES` distinct payees with non-zero
+/// weights, and its primary payee must be one of them
+fn validate_revenue_split(deps: Deps, revenue_split: &RevenueSplit) -> Result<(), ContractError> {
+    let payees = &revenue_split.payees;
+
+    if payees.is_empty() || payees.len() > MAX_PAYEES {
+        return Err(ContractError::InvalidRevenueSplit {});
+    }
+
+    let mut total_weight: u64 = 0;
+
+    for (i, payee) in payees.iter().enumerate() {
+        if payee.weight == 0 || payees[..i].iter().any(|p| p.address == payee.address) {
+            return Err(ContractError::InvalidRevenueSplit {});
+        }
+
+        deps.api.addr_validate(payee.address.as_str())?;
+
+        total_weight = total_weight
+            .checked_add(payee.weight)
+            .ok_or(ContractError::InvalidRevenueSplit {})?;
+    }
+
+    if !payees
+        .iter()
+        .any(|payee| payee.address == revenue_split.primary)
+    {
+        return Err(ContractError::InvalidRevenueSplit {});
+    }
+
+    Ok(())
+}
+
+/// Returns the prices that apply at `time`. The active scheduled price that started last
+/// wins, the collection prices apply when nothing is scheduled.
+fn current_prices(collection_info: &CollectionInfo, time: Timestamp) -> &[Price] {
+    collection_info
+        .price_schedule
+        .iter()
+        .filter(|scheduled| {
+            scheduled.start_time <= time && scheduled.end_time.is_none_or(|end| time < end)
+        })
+        .max_by_key(|scheduled| scheduled.start_time)
+        .map_or(&collection_info.prices, |scheduled| &scheduled.prices)
+}
+
+/// Returns the mint fee a collection charges in `denom` at the current block time
+fn current_mint_fee(
+    deps: Deps,
+    env: &Env,
+    collection_info: &CollectionInfo,
+    denom: &Denom,
+) -> Result<Uint128, ContractError> {
+    let time = env.block.time;
+    let unaccepted = || ContractError::UnacceptedDenom {
+        denom: denom_name(denom),
+    };
+
+    match &collection_info.pricing_mode {
+        PricingMode::Fixed => {
+            let prices = match active_phase(collection_info, time) {
+                Some(phase) => &phase.prices,
+                None => current_prices(collection_info, time),
+            };
+
+            find_price(prices, denom).ok_or_else(unaccepted)
+        }
+        PricingMode::DutchAuction(auction) => {
+            if &auction.denom != denom {
+                return Err(unaccepted());
+            }
+
+            if time < auction.start_time {
+                return Err(ContractError::AuctionNotStarted {});
+            }
+
+            Ok(auction.price_at(time))
+        }
+        PricingMode::Usd(usd_price) => {
+            if !usd_price.denoms.contains(denom) {
+                return Err(unaccepted());
+            }
+
+            let price = query_oracle_price(deps, env, denom)?;
+
+            // round up so the creator never receives less than the USD price
+            Ok(usd_price.amount.checked_div_ceil(price)?)
+        }
+    }
+}
+
+/// Fetches the micro USD price of one base unit of `denom` from the price oracle
+fn query_oracle_price(deps: Deps, env: &Env, denom: &Denom) -> Result<Decimal, ContractError> {
+    let price_oracle = CONFIG
+        .load(deps.storage)?
+        .price_oracle
+        .ok_or(ContractError::OracleNotConfigured {})?;
+
+    let response: OraclePriceResponse = deps.querier.query_wasm_smart(
+        &price_oracle.address,
+        &OracleQueryMsg::Price {
+            denom: denom.clone(),
+        },
+    )?;
+
+    if response.updated_at.plus_seconds(price_oracle.max_age) < env.block.time {
+        return Err(ContractError::StaleOraclePrice {
+            max_age: price_oracle.max_age,
+        });
+    }
+
+    if response.price.is_zero() {
+        return Err(ContractError::InvalidOraclePrice {});
+    }
+
+    Ok(response.price)
+}
+
+/// A USD price needs a non-zero amount and a valid list of payment denoms
+fn validate_usd_price(deps: Deps, usd_price: &UsdPrice) -> Result<(), ContractError> {
+    let prices: Vec<_> = usd_price
+        .denoms
+        .iter()
+        .map(|denom| Price {
+            denom: denom.clone(),
+            amount: usd_price.amount,
+        })
+        .collect();
+
+    validate_prices(deps, &prices)
+}
+
+fn validate_price_oracle(deps: Deps, price_oracle: &PriceOracle) -> Result<(), ContractError> {
+    deps.api.addr_validate(price_oracle.address.as_str())?;
+
+    if price_oracle.max_age == 0 {
+        return Err(ContractError::InvalidInput {});
+    }
+
+    Ok(())
+}
+
+/// Counts `count` new copies of a collection, failing once they'd go past its supply cap
+fn record_mints(collection_info: &mut CollectionInfo, count: u64) -> Result<(), ContractError> {
+    let minted = collection_info
+        .minted
+        .checked_add(count)
+        .ok_or(ContractError::SoldOut {})?;
+
+    if collection_info
+        .max_supply
+        .is_some_and(|max_supply| minted > max_supply)
+    {
+        return Err(ContractError::SoldOut {});
+    }
+
+    collection_info.minted = minted;
+
+    Ok(())
+}
+
+/// Counts `quantity` copies purchased by `buyer`, failing once they'd go past the
+/// collection wallet limit
+fn record_purchases(
+    storage: &mut dyn Storage,
+    collection_info: &CollectionInfo,
+    creator: &Addr,
+    buyer: &Addr,
+    quantity: u32,
+) -> Result<u32, ContractError> {
+    let key = (creator, collection_info.name.as_str(), buyer);
+    let purchased = WALLET_MINTS.may_load(storage, key)?.unwrap_or_default();
+
+    if let Some(max_per_wallet) = collection_info.max_per_wallet {
+        let remaining = max_per_wallet.saturating_sub(purchased);
+        if quantity > remaining {
+            return Err(ContractError::WalletLimitReached { remaining });
+        }
+    }
+
+    let purchased = purchased + quantity;
+    WALLET_MINTS.save(storage, key, &purchased)?;
+
+    Ok(purchased)
+}
+
+/// Counts `quantity` copies purchased by `buyer` during a sale phase, failing once they'd
+/// go past the phase wallet limit. Returns the copies purchased in the phase so far.
+fn record_phase_purchases(
+    storage: &mut dyn Storage,
+    creator: &Addr,
+    collection_name: &str,
+    phase: &SalePhase,
+    buyer: &Addr,
+    quantity: u32,
+) -> Result<u32, ContractError> {
+    let key = ((creator, collection_name), phase.name.as_str(), buyer);
+    let purchased = PHASE_WALLET_MINTS
+        .may_load(storage, key)?
+        .unwrap_or_default();
+
+    if let Some(max_per_wallet) = phase.max_per_wallet {
+        let remaining = max_per_wallet.saturating_sub(purchased);
+        if quantity > remaining {
+            return Err(ContractError::PhaseWalletLimitReached {
+                phase: phase.name.clone(),
+                remaining,
+            });
+        }
+    }
+
+    let purchased = purchased + quantity;
+    PHASE_WALLET_MINTS.save(storage, key, &purchased)?;
+
+    Ok(purchased)
+}
+
+/// Returns the sale phase running at `time`, if any
+fn active_phase(collection_info: &CollectionInfo, time: Timestamp) -> Option<&SalePhase> {
+    collection_info
+        .phases
+        .iter()
+        .find(|phase| phase.start_time <= time && phase.end_time.is_none_or(|end| time < end))
+}
+
+/// Phases are only for fixed prices. Each needs a unique name and a valid price list, and
+/// must start after the previous one has ended.
+fn validate_sale_phases(
+    deps: Deps,
+    pricing_mode: &PricingMode,
+    phases: &[SalePhase],
+) -> Result<(), ContractError> {
+    if phases.is_empty() {
+        return Ok(());
+    }
+
+    if *pricing_mode != PricingMode::Fixed || phases.len() > MAX_SALE_PHASES {
+        return Err(ContractError::InvalidSalePhases {});
+    }
+
+    for (i, phase) in phases.iter().enumerate() {
+        let previous = &phases[..i];
+
+        if phase.name.is_empty()
+            || phase.max_per_wallet == Some(0)
+            || phase.end_time.is_some_and(|end| end <= phase.start_time)
+            || previous.iter().any(|p| p.name == phase.name)
+            || previous
+                .last()
+                .is_some_and(|p| p.end_time.is_none_or(|end| end > phase.start_time))
+        {
+            return Err(ContractError::InvalidSalePhases {});
+        }
+
+        validate_prices(deps, &phase.prices)?;
+
+        if let Some(merkle_root) = &phase.merkle_root {
+            validate_merkle_root(merkle_root)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Checks a merkle root is a hex encoded sha256 digest
+fn validate_merkle_root(merkle_root: &str) -> Result<(), ContractError> {
+    parse_hash(merkle_root).ok_or(ContractError::InvalidMerkleRoot {})?;
+
+    Ok(())
+}
+
+fn parse_hash(hash: &str) -> Option<[u8; 32]> {
+    hex::decode(hash).ok()?.try_into().ok()
+}
+
+/// Verifies the merkle proof of the shopper against an allowlist root.
+///
+/// Leaves are the sha256 hash of the address, or of `address:allocation` when the
+/// allowlist grants a number of copies. Each pair of nodes is sorted before being hashed
+/// together, so proofs don't need to say which side a sibling is on.
+fn verify_allowlist(
+    merkle_root: &str,
+    buyer: &Addr,
+    params: &MintParams,
+    purchased: u32,
+    quantity: u32,
+) -> Result<(), ContractError> {
+    let leaf = match params.allocation {
+        Some(allocation) => format!("{buyer}:{allocation}"),
+        None => buyer.to_string(),
+    };
+
+    let mut hash: [u8; 32] = Sha256::digest(leaf.as_bytes()).into();
+    for sibling in params.merkle_proof.iter().flatten() {
+        let sibling = parse_hash(sibling).ok_or(ContractError::NotAllowlisted {})?;
+        let (first, second) = if hash <= sibling {
+            (hash, sibling)
+        } else {
+            (sibling, hash)
+        };
+
+        hash = Sha256::new()
+            .chain_update(first)
+            .chain_update(second)
+            .finalize()
+            .into();
+    }
+
+    if parse_hash(merkle_root) != Some(hash) {
+        return Err(ContractError::NotAllowlisted {});
+    }
+
+    if let Some(allocation) = params.allocation {
+        if purchased > allocation {
+            return Err(ContractError::AllocationExceeded {
+                remaining: allocation.saturating_sub(purchased - quantity),
+            });
+        }
+    }
+
+    Ok(())
+}
+
+/// Takes the discount of the highest quantity tier reached off the unit price
+fn apply_quantity_tier(
+    quantity_tiers: &[QuantityTier],
+    quantity: u32,
+    unit_price: Uint128,
+) -> Result<Uint128, ContractError> {
+    let Some(tier) = quantity_tiers
+        .iter()
+        .filter(|tier| tier.min_quantity <= quantity)
+        .max_by_key(|tier| tier.min_quantity)
+    else {
+        return Ok(unit_price);
+    };
+
+    Ok(unit_price.checked_sub(unit_price.checked_mul_floor(tier.discount)?)?)
+}
+
+/// Quantity tiers start from two copies, each needs its own minimum quantity and a
+/// discount below 100%
+fn validate_quantity_tiers(quantity_tiers: &[QuantityTier]) -> Result<(), ContractError> {
+    if quantity_tiers.len() > MAX_QUANTITY_TIERS {
+        return Err(ContractError::InvalidQuantityTiers {});
+    }
+
+    for (i, tier) in quantity_tiers.iter().enumerate() {
+        if tier.min_quantity < 2
+            || tier.discount.is_zero()
+            || tier.discount >= Decimal::one()
+            || quantity_tiers[..i]
+                .iter()
+                .any(|t| t.min_quantity == tier.min_quantity)
+        {
+            return Err(ContractError::InvalidQuantityTiers {});
+        }
+    }
+
+    Ok(())
+}
+
+/// A dutch auction has to fall from its start price to a non-zero floor over a
+/// non-zero interval, in at least one step
+fn validate_auction(deps: Deps, auction: &DutchAuction) -> Result<(), ContractError> {
+    if auction.end_price.is_zero()
+        || auction.start_price <= auction.end_price
+        || auction.decay_interval == 0
+        || auction.steps == Some(0)
+    {
+        return Err(ContractError::InvalidAuction {});
+    }
+
+    validate_prices(
+        deps,
+        &[Price {
+            denom: auction.denom.clone(),
+            amount: auction.start_price,
+        }],
+    )
+    .map_err(|_| ContractError::InvalidAuction {})
+}
+
+/// Whether any price list of a collection, scheduled or not, is in `denom`
+fn accepts_denom(collection_info: &CollectionInfo, denom: &Denom) -> bool {
+    let in_prices = |prices: &[Price]| find_price(prices, denom).is_some();
+
+    match &collection_info.pricing_mode {
+        PricingMode::Fixed => {
+            in_prices(&collection_info.prices)
+                || collection_info
+                    .price_schedule
+                    .iter()
+                    .any(|scheduled| in_prices(&scheduled.prices))
+                || collection_info
+                    .phases
+                    .iter()
+                    .any(|phase| in_prices(&phase.prices))
+        }
+        PricingMode::DutchAuction(auction) => &auction.denom == denom,
+        PricingMode::Usd(usd_price) => usd_price.denoms.contains(denom),
+    }
+}
+
+/// Returns the mint fee a collection charges in `denom`, if it accepts it
+fn find_price(prices: &[Price], denom: &Denom) -> Option<Uint128> {
+    prices
+        .iter()
+        .find(|price| &price.denom == denom)
+        .map(|price| price.amount)
+}
+
+/// Checks a payment covers the mint fee and returns the overpayment to refund to the shopper
+fn check_payment(
+    denom: &Denom,
+    mint_fee: Uint128,
+    received: Uint128,
+) -> Result<Uint128, ContractError> {
+    if received < mint_fee {
+        return Err(ContractError::InsufficientFunds {
+            denom: denom_name(denom),
+            expected: mint_fee,
+            received,
+        });
+    }
+
+    Ok(received - mint_fee)
+}
+
+/// A price list needs at least one entry, no zero amounts and no denom listed twice
+fn validate_prices(deps: Deps, prices: &[Price]) -> Result<(), ContractError> {
+    if prices.is_empty() {
+        return Err(ContractError::InvalidPrices {});
+    }
+
+    for (i, price) in prices.iter().enumerate() {
+        if price.amount.is_zero() || prices[..i].iter().any(|p| p.denom == price.denom) {
+            return Err(ContractError::InvalidPrices {});
+        }
+
+        match &price.denom {
+            Denom::Native(denom) if denom.is_empty() => {
+                return Err(ContractError::InvalidPrices {});
+            }
+            Denom::Cw20(addr) => {
+                deps.api.addr_validate(addr.as_str())?;
+            }
+            _ => {}
+        }
+    }
+
+    Ok(())
+}
+
+/// Native denom or CW20 contract address, for attributes and errors
+fn denom_name(denom: &Denom) -> String {
+    match denom {
+        Denom::Native(denom) => denom.clone(),
+        Denom::Cw20(token) => token.to_string(),
+    }
+}
+
+/// Builds a message paying `amount` of `denom` from this contract to `recipient`
+fn payment_msg(denom: &Denom, recipient: &Addr, amount: Uint128) -> StdResult<CosmosMsg> {
+    let msg = match denom {
+        Denom::Native(denom) => BankMsg::Send {
+            to_address: recipient.to_string(),
+            amount: vec![Coin {
+                denom: denom.clone(),
+                amount,
+            }],
+        }
+        .into(),
+        Denom::Cw20(token) => WasmMsg::Execute {
+            contract_addr: token.to_string(),
+            msg: to_json_binary(&Cw20ExecuteMsg::Transfer {
+                recipient: recipient.to_string(),
+                amount,
+            })?,
+            funds: vec![],
+        }
+        .into(),
+    };
+
+    Ok(msg)
+}
+
+pub fn execute_withdraw(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    params: WithdrawParams,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    // amounts to withdraw keyed by `denom_key`
+    let mut requested: Vec<(String, Uint128)> = vec![];
+
+    if params.withdraw_all.unwrap_or(false) {
+        for item in PLATFORM_FEES.range(deps.storage, None, None, Order::Ascending) {
+            let (key, platform_fees) = item?;
+            requested.push((key, platform_fees.available()?));
+        }
+    } else {
+        let mut add = |denom: Denom, amount: Uint128| -> Result<(), ContractError> {
+            let key = denom_key(&denom);
+            match requested.iter_mut().find(|(k, _)| *k == key) {
+                Some((_, total)) => *total = total.checked_add(amount)?,
+                None => requested.push((key, amount)),
+            }
+            Ok(())
+        };
+
+        add(
+            Denom::Native(config.denom),
+            Uint128::from(params.withdraw_amount),
+        )?;
+        for coin in params.coins.unwrap_or_default() {
+            add(Denom::Native(coin.denom), coin.amount)?;
+        }
+        for cw20_coin in params.cw20_coins.unwrap_or_default() {
+            let token = deps.api.addr_validate(&cw20_coin.address)?;
+            add(Denom::Cw20(token), cw20_coin.amount)?;
+        }
+    }
+
+    requested.retain(|(_, amount)| !amount.is_zero());
+
+    if requested.is_empty() {
+        return Err(ContractError::NothingToWithdraw {});
+    }
+
+    // only accrued platform fees can be withdrawn, the rest is owed to creators
+    for (key, amount) in &requested {
+        let mut platform_fees = PLATFORM_FEES
+            .may_load(deps.storage, key)?
+            .unwrap_or_default();
+
+        let available = platform_fees.available()?;
+        if *amount > available {
+            return Err(ContractError::InsufficientPlatformFees {
+                denom: denom_name(&denom_from_key(key)?),
+                available,
+                requested: *amount,
+            });
+        }
+
+        platform_fees.withdrawn = platform_fees.withdrawn.checked_add(*amount)?;
+        PLATFORM_FEES.save(deps.storage, key, &platform_fees)?;
+    }
+
+    let amounts = requested
+        .iter()
+        .map(|(key, amount)| Ok(format!("{amount}{}", denom_name(&denom_from_key(key)?))))
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(Response::new()
+        .add_messages(payout_msgs(&params.withdraw_address, &requested)?)
+        .add_attribute("action", "withdraw")
+        .add_attribute("withdraw_address", params.withdraw_address)
+        .add_attribute("amount", amounts.join(",")))
+}
+
+/// Builds the messages paying out amounts keyed by `denom_key`, native coins are sent
+/// together in a single bank message
+fn payout_msgs(recipient: &Addr, amounts: &[(String, Uint128)]) -> StdResult<Vec<CosmosMsg>> {
+    let mut coins = vec![];
+    let mut messages = vec![];
+
+    for (key, amount) in amounts {
+        match denom_from_key(key)? {
+            Denom::Native(denom) => coins.push(Coin {
+                denom,
+                amount: *amount,
+            }),
+            denom => messages.push(payment_msg(&denom, recipient, *amount)?),
+        }
+    }
+
+    if !coins.is_empty() {
+        messages.push(
+            BankMsg::Send {
+                to_address: recipient.to_string(),
+                amount: coins,
+            }
+            .into(),
+        );
+    }
+
+    Ok(messages)
+}
+
+pub fn execute_update_config(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    new_config: Config,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    if new_config.denom.is_empty() {
+        return Err(ContractError::InvalidInput {});
+    }
+
+    validate_mint_percent(new_config.mint_percent)?;
+
+    if let Some(price_oracle) = &new_config.price_oracle {
+        validate_price_oracle(deps.as_ref(), price_oracle)?;
+    }
+
+    // only PausePlatform and ResumePlatform change the paused state
+    CONFIG.save(
+        deps.storage,
+        &Config {
+            paused: config.paused,
+            ..new_config
+        },
+    )?;
+
+    Ok(Response::new().add_attribute("action", "update config"))
+}
+
+pub fn execute_set_platform_paused(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    paused: bool,
+) -> Result<Response, ContractError> {
+    let mut config = CONFIG.load(deps.storage)?;
+
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    config.paused = paused;
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "set platform paused")
+        .add_attribute("paused", paused.to_string()))
+}
+
+pub fn execute_set_creator_fee(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    params: SetCreatorFeeParams,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let creator = deps.api.addr_validate(&params.creator)?;
+
+    match params.mint_percent {
+        Some(mint_percent) => {
+            validate_mint_percent(mint_percent)?;
+            CREATOR_FEE_OVERRIDES.save(deps.storage, &creator, &mint_percent)?;
+        }
+        None => CREATOR_FEE_OVERRIDES.remove(deps.storage, &creator),
+    }
+
+    Ok(Response::new()
+        .add_attribute("action", "set creator fee")
+        .add_attribute("creator", creator)
+        .add_attribute(
+            "mint_percent",
+            params
+                .mint_percent
+                .map_or("default".to_string(), |mint_percent| {
+                    mint_percent.to_string()
+                }),
+        ))
+}
+
+pub fn execute_set_collection_fee(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    params: SetCollectionFeeParams,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let creator = deps.api.addr_validate(&params.creator)?;
+    let key = (&creator, params.collection_name.as_str());
+
+    // overrides only make sense for existing collections
+    CREATOR_COLLECTIONS.load(deps.storage, key)?;
+
+    match params.mint_percent {
+        Some(mint_percent) => {
+            validate_mint_percent(mint_percent)?;
+            COLLECTION_FEE_OVERRIDES.save(deps.storage, key, &mint_percent)?;
+        }
+        None => COLLECTION_FEE_OVERRIDES.remove(deps.storage, key),
+    }
+
+    Ok(Response::new()
+        .add_attribute("action", "set collection fee")
+        .add_attribute("creator", creator)
+        .add_attribute("collection_name", params.collection_name)
+        .add_attribute(
+            "mint_percent",
+            params
+                .mint_percent
+                .map_or("default".to_string(), |mint_percent| {
+                    mint_percent.to_string()
+                }),
+        ))
+}
+
+pub fn execute_update_mint_fee(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    params: UpdateMintFeeParams,
+) -> Result<Response, ContractError> {
+    let mut collection_info =
+        CREATOR_COLLECTIONS.load(deps.storage, (&info.sender, &params.collection_name))?;
+
+    validate_prices(deps.as_ref(), &params.prices)?;
+    collection_info.prices = params.prices;
+
+    CREATOR_COLLECTIONS.save(
+        deps.storage,
+        (&info.sender, &params.collection_name),
+        &collection_info,
+    )?;
+
+    Ok(Response::new().add_attribute("action", "update mint fee"))
+}
+
+pub fn execute_update_phases(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    params: UpdatePhasesParams,
+) -> Result<Response, ContractError> {
+    let mut collection_info =
+        CREATOR_COLLECTIONS.load(deps.storage, (&info.sender, &params.collection_name))?;
+
+    validate_sale_phases(deps.as_ref(), &collection_info.pricing_mode, &params.phases)?;
+    collection_info.phases = params.phases;
+
+    CREATOR_COLLECTIONS.save(
+        deps.storage,
+        (&info.sender, &params.collection_name),
+        &collection_info,
+    )?;
+
+    Ok(Response::new().add_attribute("action", "update phases"))
+}
+
+pub fn execute_update_merkle_root(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    params: UpdateMerkleRootParams,
+) -> Result<Response, ContractError> {
+    let mut collection_info =
+        CREATOR_COLLECTIONS.load(deps.storage, (&info.sender, &params.collection_name))?;
+
+    if let Some(merkle_root) = &params.merkle_root {
+        validate_merkle_root(merkle_root)?;
+    }
+
+    let merkle_root = match &params.phase_name {
+        Some(phase_name) => {
+            &mut collection_info
+                .phases
+                .iter_mut()
+                .find(|phase| &phase.name == phase_name)
+                .ok_or(ContractError::PhaseNotFound {})?
+                .merkle_root
+        }
+        None => &mut collection_info.merkle_root,
+    };
+    *merkle_root = params.merkle_root;
+
+    CREATOR_COLLECTIONS.save(
+        deps.storage,
+        (&info.sender, &params.collection_name),
+        &collection_info,
+    )?;
+
+    Ok(Response::new()
+        .add_attribute("action", "update merkle root")
+        .add_attribute("collection_name", params.collection_name))
+}
+
+pub fn execute_update_max_supply(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    params: UpdateMaxSupplyParams,
+) -> Result<Response, ContractError> {
+    let mut collection_info =
+        CREATOR_COLLECTIONS.load(deps.storage, (&info.sender, &params.collection_name))?;
+
+    // the cap can only come down, and never below what has already been minted
+    if params.max_supply < collection_info.minted
+        || params.max_supply == 0
+        || collection_info
+            .announced_supply
+            .is_some_and(|announced_supply| params.max_supply > announced_supply)
+    {
+        return Err(ContractError::InvalidMaxSupply {});
+    }
+
+    collection_info.max_supply = Some(params.max_supply);
+    // capping an unlimited collection announces its supply
+    collection_info
+        .announced_supply
+        .get_or_insert(params.max_supply);
+
+    CREATOR_COLLECTIONS.save(
+        deps.storage,
+        (&info.sender, &params.collection_name),
+        &collection_info,
+    )?;
+
+    Ok(Response::new()
+        .add_attribute("action", "update max supply")
+        .add_attribute("max_supply", params.max_supply.to_string()))
+}
+
+pub fn execute_close_collection(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    collection_name: String,
+) -> Result<Response, ContractError> {
+    let mut collection_info =
+        CREATOR_COLLECTIONS.load(deps.storage, (&info.sender, &collection_name))?;
+
+    // lowering the announced supply too keeps the supply from being raised again
+    collection_info.max_supply = Some(collection_info.minted);
+    collection_info.announced_supply = Some(collection_info.minted);
+
+    CREATOR_COLLECTIONS.save(
+        deps.storage,
+        (&info.sender, &collection_name),
+        &collection_info,
+    )?;
+
+    Ok(Response::new()
+        .add_attribute("action", "close collection")
+        .add_attribute("collection_name", collection_name)
+        .add_attribute("minted", collection_info.minted.to_string()))
+}
+
+pub fn execute_set_collection_paused(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    collection_name: String,
+    paused: bool,
+) -> Result<Response, ContractError> {
+    let mut collection_info =
+        CREATOR_COLLECTIONS.load(deps.storage, (&info.sender, &collection_name))?;
+
+    collection_info.paused = paused;
+
+    CREATOR_COLLECTIONS.save(
+        deps.storage,
+        (&info.sender, &collection_name),
+        &collection_info,
+    )?;
+
+    Ok(Response::new()
+        .add_attribute("action", "set collection paused")
+        .add_attribute("collection_name", collection_name)
+        .add_attribute("paused", paused.to_string()))
+}
+
+pub fn execute_schedule_prices(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    params: SchedulePricesParams,
+) -> Result<Response, ContractError> {
+    let mut collection_info =
+        CREATOR_COLLECTIONS.load(deps.storage, (&info.sender, &params.collection_name))?;
+
+    validate_price_schedule(deps.as_ref(), &params.price_schedule)?;
+
+    // drop anything that has already ended
+    collection_info.price_schedule = params
+        .price_schedule
+        .into_iter()
+        .filter(|scheduled| scheduled.end_time.is_none_or(|end| end > env.block.time))
+        .collect();
+
+    CREATOR_COLLECTIONS.save(
+        deps.storage,
+        (&info.sender, &params.collection_name),
+        &collection_info,
+    )?;
+
+    Ok(Response::new().add_attribute("action", "schedule prices"))
+}
+
+pub fn execute_update_revenue_split(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    params: UpdateRevenueSplitParams,
+) -> Result<Response, ContractError> {
+    let mut collection_info =
+        CREATOR_COLLECTIONS.load(deps.storage, (&info.sender, &params.collection_name))?;
+
+    if let Some(revenue_split) = &params.revenue_split {
+        validate_revenue_split(deps.as_ref(), revenue_split)?;
+    }
+    collection_info.revenue_split = params.revenue_split;
+
+    CREATOR_COLLECTIONS.save(
+        deps.storage,
+        (&info.sender, &params.collection_name),
+        &collection_info,
+    )?;
+
+    Ok(Response::new().add_attribute("action", "update revenue split"))
+}
+
+pub fn execute_update_referral_rate(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    params: UpdateReferralRateParams,
+) -> Result<Response, ContractError> {
+    let mut collection_info =
+        CREATOR_COLLECTIONS.load(deps.storage, (&info.sender, &params.collection_name))?;
+
+    validate_referral_rate(params.referral_rate)?;
+    collection_info.referral_rate = params.referral_rate;
+
+    CREATOR_COLLECTIONS.save(
+        deps.storage,
+        (&info.sender, &params.collection_name),
+        &collection_info,
+    )?;
+
+    Ok(Response::new()
+        .add_attribute("action", "update referral rate")
+        .add_attribute("referral_rate", params.referral_rate.to_string()))
+}
+
+pub fn execute_register_coupon(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    params: CouponParams,
+) -> Result<Response, ContractError> {
+    // only the creator of the collection can register coupons for it
+    CREATOR_COLLECTIONS.load(deps.storage, (&info.sender, &params.collection_name))?;
+
+    let code_hash = parse_code_hash(&params.code_hash)?;
+
+    let valid_discount = match &params.discount {
+        Discount::Percent(percent) => !percent.is_zero() && *percent <= Decimal::one(),
+        Discount::Fixed { amount, .. } => !amount.is_zero(),
+    };
+
+    if !valid_discount || params.max_redemptions == 0 || params.expires <= env.block.time {
+        return Err(ContractError::InvalidCoupon {});
+    }
+
+    let key = (
+        &info.sender,
+        params.collection_name.as_str(),
+        code_hash.as_str(),
+    );
+
+    if COUPONS.has(deps.storage, key) {
+        return Err(ContractError::CouponExists {});
+    }
+
+    let coupon = Coupon {
+        discount: params.discount,
+        max_redemptions: params.max_redemptions,
+        redemptions: 0,
+        expires: params.expires,
+    };
+
+    COUPONS.save(deps.storage, key, &coupon)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "register coupon")
+        .add_attribute("code_hash", code_hash))
+}
+
+pub fn execute_remove_coupon(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    params: RemoveCouponParams,
+) -> Result<Response, ContractError> {
+    let code_hash = parse_code_hash(&params.code_hash)?;
+    let key = (
+        &info.sender,
+        params.collection_name.as_str(),
+        code_hash.as_str(),
+    );
+
+    if !COUPONS.has(deps.storage, key) {
+        return Err(ContractError::CouponNotFound {});
+    }
+
+    COUPONS.remove(deps.storage, key);
+
+    Ok(Response::new()
+        .add_attribute("action", "remove coupon")
+        .add_attribute("code_hash", code_hash))
+}
+
+/// Checks a coupon code hash is a hex encoded sha256 digest and lowercases it
+fn parse_code_hash(code_hash: &str) -> Result<String, ContractError> {
+    match hex::decode(code_hash) {
+        Ok(bytes) if bytes.len() == 32 => Ok(hex::encode(bytes)),
+        _ => Err(ContractError::InvalidCoupon {}),
+    }
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::Config {} => to_json_binary(&query_config(deps)?),
+        QueryMsg::MintPercent {
+            creator,
+            collection_name,
+        } => to_json_binary(&query_mint_percent(deps, creator, collection_name)?),
+        QueryMsg::TokenIndex {} => to_json_binary(&query_token_index(deps)?),
+        QueryMsg::CreatorCollections {
+            creator,
+            collection_name,
+        } => to_json_binary(&query_creator_collections(deps, creator, collection_name)?),
+        QueryMsg::MintAllowance {
+            creator,
+            collection_name,
+            buyer,
+        } => to_json_binary(&query_mint_allowance(
+            deps,
+            creator,
+            collection_name,
+            buyer,
+        )?),
+        QueryMsg::CreatorBalance { creator } => {
+            to_json_binary(&query_creator_balance(deps, creator)?)
+        }
+        QueryMsg::PlatformFees { denom } => to_json_binary(&query_platform_fees(deps, denom)?),
+        QueryMsg::CreatorTips { creator } => to_json_binary(&query_creator_tips(deps, creator)?),
+        QueryMsg::CollectionTips {
+            creator,
+            collection_name,
+        } => to_json_binary(&query_collection_tips(deps, creator, collection_name)?),
+        QueryMsg::ReferralEarnings { referrer } => {
+            to_json_binary(&query_referral_earnings(deps, referrer)?)
+        }
+        QueryMsg::PriceSchedule {
+            creator,
+            collection_name,
+        } => to_json_binary(&query_price_schedule(deps, env, creator, collection_name)?),
+        QueryMsg::CurrentPhase {
+            creator,
+            collection_name,
+        } => to_json_binary(&query_current_phase(deps, env, creator, collection_name)?),
+        QueryMsg::AuctionPrice {
+            creator,
+            collection_name,
+        } => to_json_binary(&query_auction_price(deps, env, creator, collection_name)?),
+        QueryMsg::Escrow {
+            collection_address,
+            token_id,
+        } => to_json_binary(&query_escrow(deps, collection_address, token_id)?),
+        QueryMsg::IsSubscriptionActive { owner, collection } => {
+            to_json_binary(&query_is_subscription_active(deps, env, owner, collection)?)
+        }
+        QueryMsg::Coupon {
+            creator,
+            collection_name,
+            code_hash,
+        } => to_json_binary(&query_coupon(deps, creator, collection_name, code_hash)?),
+    }
+}
+
+fn query_config(deps: Deps) -> StdResult<Config> {
+    let config = CONFIG.load(deps.storage)?;
+    Ok(config)
+}
+
+fn query_mint_percent(
+    deps: Deps,
+    creator: Addr,
+    collection_name: String,
+) -> StdResult<MintPercentResponse> {
+    let (mint_percent, source) = mint_percent(deps.storage, &creator, &collection_name)?;
+    Ok(MintPercentResponse {
+        mint_percent,
+        source,
+    })
+}
+
+fn query_token_index(deps: Deps) -> StdResult<u64> {
+    let index = TOKEN_INDEX.load(deps.storage)?;
+    Ok(index)
+}
+
+fn query_creator_collections(
+    deps: Deps,
+    creator: Addr,
+    collection_name: String,
+) -> StdResult<CollectionInfo> {
+    let mut collection_info = CREATOR_COLLECTIONS
+        .may_load(deps.storage, (&creator, &collection_name))?
+        .unwrap_or_default();
+
+    // a platform pause stops the sales of every collection
+    collection_info.paused |= CONFIG.load(deps.storage)?.paused;
+
+    Ok(collection_info)
+}
+
+fn query_mint_allowance(
+    deps: Deps,
+    creator: Addr,
+    collection_name: String,
+    buyer: Addr,
+) -> StdResult<MintAllowanceResponse> {
+    let collection_info = CREATOR_COLLECTIONS.load(deps.storage, (&creator, &collection_name))?;
+
+    let purchased = WALLET_MINTS
+        .may_load(deps.storage, (&creator, &collection_name, &buyer))?
+        .unwrap_or_default();
+
+    Ok(MintAllowanceResponse {
+        purchased,
+        remaining: collection_info
+            .max_per_wallet
+            .map(|max_per_wallet| max_per_wallet.saturating_sub(purchased)),
+    })
+}
+
+fn query_creator_balance(deps: Deps, creator: Addr) -> StdResult<CreatorBalanceResponse> {
+    let balances = load_denom_balances(deps.storage, CREATOR_BALANCES, &creator)?;
+    Ok(CreatorBalanceResponse { balances })
+}
+
+fn query_platform_fees(deps: Deps, denom: Denom) -> StdResult<PlatformFeesResponse> {
+    let platform_fees = PLATFORM_FEES
+        .may_load(deps.storage, &denom_key(&denom))?
+        .unwrap_or_default();
+
+    Ok(PlatformFeesResponse {
+        denom,
+        accrued: platform_fees.accrued,
+        withdrawn: platform_fees.withdrawn,
+        available: platform_fees.available()?,
+    })
+}
+
+fn query_creator_tips(deps: Deps, creator: Addr) -> StdResult<TipsResponse> {
+    let tips = load_denom_balances(deps.storage, CREATOR_TIPS, &creator)?;
+    Ok(TipsResponse { tips })
+}
+
+fn query_collection_tips(
+    deps: Deps,
+    creator: Addr,
+    collection_name: String,
+) -> StdResult<TipsResponse> {
+    let tips = COLLECTION_TIPS
+        .prefix((&creator, &collection_name))
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|item| {
+            let (key, amount) = item?;
+            Ok(DenomBalance {
+                denom: denom_from_key(&key)?,
+                amount,
+            })
+        })
+        .collect::<StdResult<_>>()?;
+
+    Ok(TipsResponse { tips })
+}
+
+fn query_referral_earnings(deps: Deps, referrer: Addr) -> StdResult<ReferralEarningsResponse> {
+    let earnings = load_denom_balances(deps.storage, REFERRAL_EARNINGS, &referrer)?;
+    Ok(ReferralEarningsResponse { earnings })
+}
+
+/// Loads every denom balance an address has in a map keyed by address and `denom_key`
+fn load_denom_balances(
+    storage: &dyn Storage,
+    balances: Map<(&Addr, &str), Uint128>,
+    address: &Addr,
+) -> StdResult<Vec<DenomBalance>> {
+    balances
+        .prefix(address)
+        .range(storage, None, None, Order::Ascending)
+        .map(|item| {
+            let (key, amount) = item?;
+            Ok(DenomBalance {
+                denom: denom_from_key(&key)?,
+                amount,
+            })
+        })
+        .collect()
+}
+
+fn query_price_schedule(
+    deps: Deps,
+    env: Env,
+    creator: Addr,
+    collection_name: String,
+) -> StdResult<PriceScheduleResponse> {
+    let collection_info = CREATOR_COLLECTIONS.load(deps.storage, (&creator, &collection_name))?;
+
+    let current = current_prices(&collection_info, env.block.time).to_vec();
+
+    let mut upcoming: Vec<_> = collection_info
+        .price_schedule
+        .into_iter()
+        .filter(|scheduled| scheduled.start_time > env.block.time)
+        .collect();
+    upcoming.sort_by_key(|scheduled| scheduled.start_time);
+
+    Ok(PriceScheduleResponse { current, upcoming })
+}
+
+fn query_current_phase(
+    deps: Deps,
+    env: Env,
+    creator: Addr,
+    collection_name: String,
+) -> StdResult<CurrentPhaseResponse> {
+    let collection_info = CREATOR_COLLECTIONS.load(deps.storage, (&creator, &collection_name))?;
+
+    Ok(CurrentPhaseResponse {
+        phase: active_phase(&collection_info, env.block.time).cloned(),
+    })
+}
+
+fn query_auction_price(
+    deps: Deps,
+    env: Env,
+    creator: Addr,
+    collection_name: String,
+) -> StdResult<AuctionPriceResponse> {
+    let collection_info = CREATOR_COLLECTIONS.load(deps.storage, (&creator, &collection_name))?;
+
+    let PricingMode::DutchAuction(auction) = collection_info.pricing_mode else {
+        return Err(StdError::generic_err("collection is not a dutch auction"));
+    };
+
+    Ok(AuctionPriceResponse {
+        price: auction.price_at(env.block.time),
+        end_time: auction.end_time(),
+        denom: auction.denom,
+    })
+}
+
+fn query_escrow(
+    deps: Deps,
+    collection_address: String,
+    token_id: String,
+) -> StdResult<EscrowResponse> {
+    let collection_address = deps.api.addr_validate(&collection_address)?;
+    let key = (&collection_address, token_id.as_str());
+
+    let escrow = TOKEN_ESCROWS
+        .may_load(deps.storage, key)?
+        .map(|(creator, escrow_id)| ESCROWS.load(deps.storage, (&creator, escrow_id)))
+        .transpose()?;
+
+    Ok(EscrowResponse {
+        escrow,
+        refunded_at: REFUNDED_TOKENS.may_load(deps.storage, key)?,
+    })
+}
+
+fn query_is_subscription_active(
+    deps: Deps,
+    env: Env,
+    owner: String,
+    collection: String,
+) -> StdResult<SubscriptionStatusResponse> {
+    let owner = deps.api.addr_validate(&owner)?;
+    let collection = deps.api.addr_validate(&collection)?;
+
+    let expires = SUBSCRIBER_EXPIRIES.may_load(deps.storage, (&collection, &owner))?;
+
+    Ok(SubscriptionStatusResponse {
+        active: expires.is_some_and(|expires| env.block.time < expires),
+        expires,
+    })
+}
+
+fn query_coupon(
+    deps: Deps,
+    creator: Addr,
+    collection_name: String,
+    code_hash: String,
+) -> StdResult<Coupon> {
+    COUPONS.load(
+        deps.storage,
+        (&creator, &collection_name, &code_hash.to_lowercase()),
+    )
 }
 
 // Reply callback triggered from cw721 contract instantiation in instantiate()
@@ -1,4 +1,7 @@
-use cosmwasm_std::StdError;
+use cosmwasm_std::{
+    CheckedMultiplyFractionError, CheckedMultiplyRatioError, Decimal, OverflowError, StdError,
+    Uint128,
+};
 use thiserror::Error;
 
 #[derive(Error, Debug, PartialEq)]
@@ -6,6 +9,15 @@ pub enum ContractError {
     #[error("{0}")]
     Std(#[from] StdError),
 
+    #[error("{0}")]
+    Overflow(#[from] OverflowError),
+
+    #[error("{0}")]
+    CheckedMultiplyFraction(#[from] CheckedMultiplyFractionError),
+
+    #[error("{0}")]
+    CheckedMultiplyRatio(#[from] CheckedMultiplyRatioError),
+
     #[error("Unauthorized")]
     Unauthorized {},
 
@@ -24,8 +36,154 @@ pub enum ContractError {
     #[error("pending collection not found")]
     PendingCollectionNotFound {},
 
-    #[error("incorrect funds")]
-    IncorrectFunds {},
+    #[error("collection address doesn't match the collection")]
+    CollectionAddressMismatch {},
+
+    #[error("no funds sent")]
+    NoFunds {},
+
+    #[error("only one denom can be sent at a time")]
+    MultipleDenoms {},
+
+    #[error("{denom} is not accepted for this collection")]
+    UnacceptedDenom { denom: String },
+
+    #[error("insufficient funds: expected {expected}{denom}, received {received}{denom}")]
+    InsufficientFunds {
+        denom: String,
+        expected: Uint128,
+        received: Uint128,
+    },
+
+    #[error("mint percent cannot exceed {max}")]
+    MintPercentTooHigh { max: Decimal },
+
+    #[error("invalid price list")]
+    InvalidPrices {},
+
+    #[error("invalid price schedule")]
+    InvalidPriceSchedule {},
+
+    #[error("invalid dutch auction")]
+    InvalidAuction {},
+
+    #[error("dutch auction hasn't started yet")]
+    AuctionNotStarted {},
+
+    #[error("invalid quantity tiers")]
+    InvalidQuantityTiers {},
+
+    #[error("quantity must be between 1 and {max}")]
+    InvalidQuantity { max: u32 },
+
+    #[error("gift allowance exceeded, {remaining} gifts remaining")]
+    GiftAllowanceExceeded { remaining: u32 },
+
+    #[error("refund window must be between 1 second and {max} seconds")]
+    InvalidRefundWindow { max: u64 },
+
+    #[error("no refund available for this token")]
+    RefundNotAvailable {},
+
+    #[error("refund window has closed")]
+    RefundWindowClosed {},
+
+    #[error("invalid subscription period")]
+    InvalidSubscriptionPeriod {},
+
+    #[error("subscription not found")]
+    SubscriptionNotFound {},
+
+    #[error("no price oracle configured")]
+    OracleNotConfigured {},
+
+    #[error("oracle price is older than {max_age} seconds")]
+    StaleOraclePrice { max_age: u64 },
+
+    #[error("invalid oracle price")]
+    InvalidOraclePrice {},
+
+    #[error("price of {price} is above the max payment of {max_payment}")]
+    MaxPaymentExceeded {
+        max_payment: Uint128,
+        price: Uint128,
+    },
+
+    #[error("sold out")]
+    SoldOut {},
+
+    #[error("invalid max supply")]
+    InvalidMaxSupply {},
+
+    #[error("wallet limit reached, {remaining} copies remaining")]
+    WalletLimitReached { remaining: u32 },
+
+    #[error("invalid sale phases")]
+    InvalidSalePhases {},
+
+    #[error("no sale phase is active")]
+    SaleNotActive {},
+
+    #[error("wallet limit for the {phase} phase reached, {remaining} copies remaining")]
+    PhaseWalletLimitReached { phase: String, remaining: u32 },
+
+    #[error("platform is paused")]
+    PlatformPaused {},
+
+    #[error("collection is paused")]
+    CollectionPaused {},
+
+    #[error("sale phase not found")]
+    PhaseNotFound {},
+
+    #[error("invalid merkle root")]
+    InvalidMerkleRoot {},
+
+    #[error("address is not on the allowlist")]
+    NotAllowlisted {},
+
+    #[error("allowlist allocation reached, {remaining} copies remaining")]
+    AllocationExceeded { remaining: u32 },
+
+    #[error("invalid revenue split")]
+    InvalidRevenueSplit {},
+
+    #[error("referral rate cannot exceed 1")]
+    InvalidReferralRate {},
+
+    #[error("shoppers cannot refer themselves")]
+    InvalidReferrer {},
+
+    #[error("invalid coupon")]
+    InvalidCoupon {},
+
+    #[error("coupon already registered")]
+    CouponExists {},
+
+    #[error("coupon not found")]
+    CouponNotFound {},
+
+    #[error("coupon expired")]
+    CouponExpired {},
+
+    #[error("coupon has no redemptions left")]
+    CouponExhausted {},
+
+    #[error("coupon does not apply to {denom}")]
+    CouponNotApplicable { denom: String },
+
+    #[error("nothing to claim")]
+    NothingToClaim {},
+
+    #[error("insufficient platform fees in {denom}: {available} available, {requested} requested")]
+    InsufficientPlatformFees {
+        denom: String,
+        available: Uint128,
+        requested: Uint128,
+    },
+
+    #[error("nothing to withdraw")]
+    NothingToWithdraw {},
     // Add any other custom errors you like here.
     // Look at https://docs.rs/thiserror/1.0.21/thiserror/ for details.
 }
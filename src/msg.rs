@@ -1,22 +1,93 @@
-use crate::state::{self, CollectionInfo};
-use crate::state::{Config, UpdateMintFeeParams, WithdrawParams};
+use crate::state::{
+    self, CollectionInfo, Coupon, Escrow, GiftMintParams, Price, PriceOracle, PricingMode,
+    QuantityTier, RenewParams, RevenueSplit, SalePhase, ScheduledPrice, SetCollectionFeeParams,
+    SetCreatorFeeParams, UpdateMaxSupplyParams, UpdateMerkleRootParams, UpdatePhasesParams,
+};
+use crate::state::{
+    Config, CouponParams, RemoveCouponParams, SchedulePricesParams, UpdateMintFeeParams,
+    UpdateReferralRateParams, UpdateRevenueSplitParams, WithdrawParams,
+};
 use cosmwasm_schema::{cw_serde, QueryResponses};
-use cosmwasm_std::Addr;
+use cosmwasm_std::{Addr, Decimal, Timestamp, Uint128};
+use cw20::{Cw20ReceiveMsg, Denom};
 use state::{CollectionParams, MintParams};
 
 #[cw_serde]
 pub struct InstantiateMsg {
     pub owner: Option<String>,
-    pub mint_percent: u128,
+    pub mint_percent: Decimal,
+    pub denom: String,
+    pub price_oracle: Option<PriceOracle>,
+    pub charge_tip_fee: bool,
 }
 
 #[cw_serde]
 pub enum ExecuteMsg {
     CreateCollection(CollectionParams),
     MintNFT(MintParams),
+    GiftMint(GiftMintParams),
+    Renew(RenewParams),
     Withdraw(WithdrawParams),
     UpdateConfig(Config),
+    /// Stops new collections and mints across the platform, owner only
+    PausePlatform {},
+    ResumePlatform {},
+    SetCreatorFee(SetCreatorFeeParams),
+    SetCollectionFee(SetCollectionFeeParams),
     UpdateMintFee(UpdateMintFeeParams),
+    SchedulePrices(SchedulePricesParams),
+    UpdatePhases(UpdatePhasesParams),
+    UpdateMerkleRoot(UpdateMerkleRootParams),
+    UpdateMaxSupply(UpdateMaxSupplyParams),
+    /// Stops all further mints by capping the supply at the copies minted so far
+    CloseCollection {
+        collection_name: String,
+    },
+    /// Stops sales of a collection until it is resumed
+    PauseCollection {
+        collection_name: String,
+    },
+    ResumeCollection {
+        collection_name: String,
+    },
+    UpdateRevenueSplit(UpdateRevenueSplitParams),
+    UpdateReferralRate(UpdateReferralRateParams),
+    RegisterCoupon(CouponParams),
+    RemoveCoupon(RemoveCouponParams),
+    Receive(Cw20ReceiveMsg),
+    /// Pays out the creator earnings, in every denom when `denoms` is `None`
+    ClaimEarnings {
+        denoms: Option<Vec<Denom>>,
+    },
+    /// Refunds the sale a token was bought in while its refund window is open and burns
+    /// every token of the sale
+    RequestRefund {
+        collection_address: String,
+        token_id: String,
+    },
+    /// Sends the attached funds to the creator of a collection
+    Tip {
+        creator: String,
+        collection_name: String,
+        memo: Option<String>,
+    },
+    /// Credits a creator's escrowed sales whose refund window has closed and hands their
+    /// tokens over to the buyers
+    ReleaseEscrow {
+        creator: String,
+    },
+}
+
+/// Messages that can be sent along with CW20 tokens through `Cw20ExecuteMsg::Send`
+#[cw_serde]
+pub enum ReceiveMsg {
+    MintNFT(MintParams),
+    Renew(RenewParams),
+    Tip {
+        creator: String,
+        collection_name: String,
+        memo: Option<String>,
+    },
 }
 
 #[cw_serde]
@@ -26,6 +97,13 @@ pub enum QueryMsg {
     #[returns(ConfigResponse)]
     Config {},
 
+    // Query for the platform fee that applies to a collection
+    #[returns(MintPercentResponse)]
+    MintPercent {
+        creator: Addr,
+        collection_name: String,
+    },
+
     // Query for the current token index
     #[returns(u64)]
     TokenIndex {},
@@ -36,6 +114,93 @@ pub enum QueryMsg {
         creator: Addr,
         collection_name: String,
     },
+
+    // Query for how many more copies of a collection a buyer can purchase
+    #[returns(MintAllowanceResponse)]
+    MintAllowance {
+        creator: Addr,
+        collection_name: String,
+        buyer: Addr,
+    },
+
+    // Query for the earnings a creator can claim
+    #[returns(CreatorBalanceResponse)]
+    CreatorBalance { creator: Addr },
+
+    // Query for the platform fees accrued in a denom
+    #[returns(PlatformFeesResponse)]
+    PlatformFees { denom: Denom },
+
+    // Query for the lifetime tips received by a creator
+    #[returns(TipsResponse)]
+    CreatorTips { creator: Addr },
+
+    // Query for the lifetime tips received on a collection
+    #[returns(TipsResponse)]
+    CollectionTips {
+        creator: Addr,
+        collection_name: String,
+    },
+
+    // Query for the lifetime commissions earned by a referrer
+    #[returns(ReferralEarningsResponse)]
+    ReferralEarnings { referrer: Addr },
+
+    // Query for the prices that apply now and the scheduled ones
+    #[returns(PriceScheduleResponse)]
+    PriceSchedule {
+        creator: Addr,
+        collection_name: String,
+    },
+
+    // Query for the sale phase that is active now
+    #[returns(CurrentPhaseResponse)]
+    CurrentPhase {
+        creator: Addr,
+        collection_name: String,
+    },
+
+    // Query for the current price of a dutch auction collection
+    #[returns(AuctionPriceResponse)]
+    AuctionPrice {
+        creator: Addr,
+        collection_name: String,
+    },
+
+    // Query for the escrow and refund status of a token
+    #[returns(EscrowResponse)]
+    Escrow {
+        collection_address: String,
+        token_id: String,
+    },
+
+    // Query for whether an owner holds an unexpired subscription token of a collection
+    #[returns(SubscriptionStatusResponse)]
+    IsSubscriptionActive { owner: String, collection: String },
+
+    // Query for a coupon by the hex sha256 hash of its code
+    #[returns(Coupon)]
+    Coupon {
+        creator: Addr,
+        collection_name: String,
+        code_hash: String,
+    },
+}
+
+/// Query the factory sends to the price oracle
+#[cw_serde]
+#[derive(QueryResponses)]
+pub enum OracleQueryMsg {
+    #[returns(OraclePriceResponse)]
+    Price { denom: Denom },
+}
+
+#[cw_serde]
+pub struct OraclePriceResponse {
+    /// Micro USD one base unit of the denom is worth, e.g. `2` for a 6 decimal token
+    /// trading at $2
+    pub price: Decimal,
+    pub updated_at: Timestamp,
 }
 
 // We define a custom struct for each query response
@@ -43,7 +208,23 @@ pub enum QueryMsg {
 pub struct CollectionInfoResponse {
     pub name: String,
     pub symbol: String,
-    pub mint_fee: u128,
+    pub prices: Vec<Price>,
+    pub price_schedule: Vec<ScheduledPrice>,
+    pub pricing_mode: PricingMode,
+    pub phases: Vec<SalePhase>,
+    pub quantity_tiers: Vec<QuantityTier>,
+    pub revenue_split: Option<RevenueSplit>,
+    pub referral_rate: Decimal,
+    pub gift_allowance: Option<u32>,
+    pub gifted: u32,
+    pub max_supply: Option<u64>,
+    pub announced_supply: Option<u64>,
+    pub minted: u64,
+    pub max_per_wallet: Option<u32>,
+    pub merkle_root: Option<String>,
+    pub refund_window: Option<u64>,
+    pub subscription_period: Option<u64>,
+    pub paused: bool,
     pub collection_address: Option<Addr>,
 }
 
@@ -52,7 +233,23 @@ impl From<CollectionInfo> for CollectionInfoResponse {
         CollectionInfoResponse {
             name: collection_info.name,
             symbol: collection_info.symbol,
-            mint_fee: collection_info.mint_fee,
+            prices: collection_info.prices,
+            price_schedule: collection_info.price_schedule,
+            pricing_mode: collection_info.pricing_mode,
+            phases: collection_info.phases,
+            quantity_tiers: collection_info.quantity_tiers,
+            revenue_split: collection_info.revenue_split,
+            referral_rate: collection_info.referral_rate,
+            gift_allowance: collection_info.gift_allowance,
+            gifted: collection_info.gifted,
+            max_supply: collection_info.max_supply,
+            announced_supply: collection_info.announced_supply,
+            minted: collection_info.minted,
+            max_per_wallet: collection_info.max_per_wallet,
+            merkle_root: collection_info.merkle_root,
+            refund_window: collection_info.refund_window,
+            subscription_period: collection_info.subscription_period,
+            paused: collection_info.paused,
             collection_address: collection_info.collection_address,
         }
     }
@@ -61,7 +258,11 @@ impl From<CollectionInfo> for CollectionInfoResponse {
 #[cw_serde]
 pub struct ConfigResponse {
     pub owner: Addr,
-    pub mint_percent: u128,
+    pub mint_percent: Decimal,
+    pub denom: String,
+    pub price_oracle: Option<PriceOracle>,
+    pub charge_tip_fee: bool,
+    pub paused: bool,
 }
 
 impl From<Config> for ConfigResponse {
@@ -69,6 +270,98 @@ impl From<Config> for ConfigResponse {
         ConfigResponse {
             owner: config.owner,
             mint_percent: config.mint_percent,
+            denom: config.denom,
+            price_oracle: config.price_oracle,
+            charge_tip_fee: config.charge_tip_fee,
+            paused: config.paused,
         }
     }
 }
+
+/// Where the platform fee of a collection comes from
+#[cw_serde]
+pub enum FeeSource {
+    Collection,
+    Creator,
+    Config,
+}
+
+#[cw_serde]
+pub struct MintPercentResponse {
+    pub mint_percent: Decimal,
+    pub source: FeeSource,
+}
+
+#[cw_serde]
+pub struct MintAllowanceResponse {
+    /// Copies the buyer has purchased so far
+    pub purchased: u32,
+    /// Copies the buyer can still purchase, unlimited when `None`
+    pub remaining: Option<u32>,
+}
+
+#[cw_serde]
+pub struct DenomBalance {
+    pub denom: Denom,
+    pub amount: Uint128,
+}
+
+#[cw_serde]
+pub struct CreatorBalanceResponse {
+    pub balances: Vec<DenomBalance>,
+}
+
+#[cw_serde]
+pub struct PriceScheduleResponse {
+    /// Prices that apply at the current block time
+    pub current: Vec<Price>,
+    /// Scheduled prices that haven't started yet, earliest first
+    pub upcoming: Vec<ScheduledPrice>,
+}
+
+#[cw_serde]
+pub struct CurrentPhaseResponse {
+    /// `None` between phases, before the first one and after the last one
+    pub phase: Option<SalePhase>,
+}
+
+#[cw_serde]
+pub struct AuctionPriceResponse {
+    pub denom: Denom,
+    /// Price at the current block time
+    pub price: Uint128,
+    /// Time the price reaches the floor
+    pub end_time: Timestamp,
+}
+
+#[cw_serde]
+pub struct EscrowResponse {
+    /// Sale the token was bought in, while it is still in escrow
+    pub escrow: Option<Escrow>,
+    pub refunded_at: Option<Timestamp>,
+}
+
+#[cw_serde]
+pub struct SubscriptionStatusResponse {
+    pub active: bool,
+    /// Latest expiry of the owner's subscription tokens
+    pub expires: Option<Timestamp>,
+}
+
+#[cw_serde]
+pub struct TipsResponse {
+    pub tips: Vec<DenomBalance>,
+}
+
+#[cw_serde]
+pub struct ReferralEarningsResponse {
+    pub earnings: Vec<DenomBalance>,
+}
+
+#[cw_serde]
+pub struct PlatformFeesResponse {
+    pub denom: Denom,
+    pub accrued: Uint128,
+    pub withdrawn: Uint128,
+    pub available: Uint128,
+}
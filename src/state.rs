@@ -1,12 +1,148 @@
 use cosmwasm_schema::cw_serde;
-use cosmwasm_std::{Addr, StdResult, Storage};
+use cosmwasm_std::{Addr, Coin, Decimal, Empty, StdError, StdResult, Storage, Timestamp, Uint128};
+use cw20::{Cw20Coin, Denom};
 use cw_storage_plus::{Item, Map};
 use serde::{Deserialize, Serialize};
 
 #[cw_serde]
 pub struct Config {
     pub owner: Addr,
-    pub mint_percent: u128,
+    /// Share of every sale kept by the platform, e.g. `0.025` for 2.5%
+    pub mint_percent: Decimal,
+    /// Native denom mint fees and withdrawals are paid in
+    pub denom: String,
+    /// Oracle that converts USD prices to payment denoms, USD prices can't be paid when
+    /// `None`
+    pub price_oracle: Option<PriceOracle>,
+    /// Whether the platform keeps `mint_percent` of tips as well
+    pub charge_tip_fee: bool,
+    /// Blocks new collections and sales across the platform, withdrawals and claims keep
+    /// working. `UpdateConfig` leaves it as it is.
+    pub paused: bool,
+}
+
+#[cw_serde]
+pub struct PriceOracle {
+    /// Contract answering `OracleQueryMsg`
+    pub address: Addr,
+    /// Seconds after which an oracle price is too old to use
+    pub max_age: u64,
+}
+
+/// Price of a product in a single native denom or CW20 token
+#[cw_serde]
+pub struct Price {
+    pub denom: Denom,
+    pub amount: Uint128,
+}
+
+/// Price list that replaces the collection prices for a period of time
+#[cw_serde]
+pub struct ScheduledPrice {
+    pub start_time: Timestamp,
+    /// The change is permanent when `None`
+    pub end_time: Option<Timestamp>,
+    pub prices: Vec<Price>,
+}
+
+/// Window of a sale with its own prices and wallet limit, e.g. a presale
+#[cw_serde]
+pub struct SalePhase {
+    pub name: String,
+    pub start_time: Timestamp,
+    /// The phase runs until the collection closes when `None`
+    pub end_time: Option<Timestamp>,
+    pub prices: Vec<Price>,
+    /// Most copies a single buyer can purchase during the phase, unlimited when `None`
+    pub max_per_wallet: Option<u32>,
+    /// Hex encoded root of the phase allowlist, replaces the collection allowlist during
+    /// the phase
+    pub merkle_root: Option<String>,
+}
+
+/// How the mint fee of a collection is worked out
+#[cw_serde]
+#[derive(Default)]
+pub enum PricingMode {
+    /// The collection prices, or the scheduled price that is active
+    #[default]
+    Fixed,
+    DutchAuction(DutchAuction),
+    /// Price set in USD and converted to the payment denom by the price oracle
+    Usd(UsdPrice),
+}
+
+#[cw_serde]
+pub struct UsdPrice {
+    /// Price in micro USD, e.g. `5000000` for $5
+    pub amount: Uint128,
+    /// Accepted payment denoms
+    pub denoms: Vec<Denom>,
+}
+
+/// Price that starts high and falls to a floor over time, in a single denom
+#[cw_serde]
+pub struct DutchAuction {
+    pub denom: Denom,
+    pub start_price: Uint128,
+    /// Floor the price stops falling at
+    pub end_price: Uint128,
+    pub start_time: Timestamp,
+    /// Seconds it takes the price to fall from `start_price` to `end_price`
+    pub decay_interval: u64,
+    /// Number of equal drops the price falls in, it falls linearly when `None`
+    pub steps: Option<u32>,
+}
+
+impl DutchAuction {
+    /// Time the price reaches the floor
+    pub fn end_time(&self) -> Timestamp {
+        self.start_time.plus_seconds(self.decay_interval)
+    }
+
+    /// Price at `time`, rounded down
+    pub fn price_at(&self, time: Timestamp) -> Uint128 {
+        let elapsed = time.seconds().saturating_sub(self.start_time.seconds());
+        if elapsed >= self.decay_interval {
+            return self.end_price;
+        }
+
+        let decay = self.start_price - self.end_price;
+        let drop = match self.steps {
+            Some(steps) => {
+                // u128 keeps long auctions with many steps from overflowing
+                let steps_taken =
+                    u128::from(elapsed) * u128::from(steps) / u128::from(self.decay_interval);
+                decay.multiply_ratio(steps_taken, steps)
+            }
+            None => decay.multiply_ratio(elapsed, self.decay_interval),
+        };
+
+        self.start_price - drop
+    }
+}
+
+/// Discount on the unit price when buying at least `min_quantity` copies at once
+#[cw_serde]
+pub struct QuantityTier {
+    pub min_quantity: u32,
+    /// Fraction of the unit price taken off, e.g. `0.2` for 20% off
+    pub discount: Decimal,
+}
+
+/// A payout address and its weight in a revenue split
+#[cw_serde]
+pub struct Payee {
+    pub address: Addr,
+    pub weight: u64,
+}
+
+/// Divides the creator share of every sale among collaborators by weight
+#[cw_serde]
+pub struct RevenueSplit {
+    pub payees: Vec<Payee>,
+    /// Payee that receives any rounding dust, must be one of `payees`
+    pub primary: Addr,
 }
 
 #[cw_serde]
@@ -14,7 +150,44 @@ pub struct Config {
 pub struct CollectionInfo {
     pub name: String,
     pub symbol: String,
-    pub mint_fee: u128,
+    /// Accepted payment denoms, each with its own mint fee
+    pub prices: Vec<Price>,
+    /// Sales and price changes that override `prices` while they are active
+    pub price_schedule: Vec<ScheduledPrice>,
+    /// Dutch auction and USD priced collections ignore `prices` and `price_schedule`
+    pub pricing_mode: PricingMode,
+    /// Ordered sale phases, mints are only accepted during one when there are any. Phase
+    /// prices replace `prices` and `price_schedule`.
+    pub phases: Vec<SalePhase>,
+    /// Bulk discounts, the tier with the highest `min_quantity` reached applies
+    pub quantity_tiers: Vec<QuantityTier>,
+    /// Creator share payees, the whole share goes to the creator when `None`
+    pub revenue_split: Option<RevenueSplit>,
+    /// Share of the creator earnings paid to the referrer of a sale
+    pub referral_rate: Decimal,
+    /// Most copies the creator can gift, unlimited when `None`
+    pub gift_allowance: Option<u32>,
+    /// Copies gifted so far, these aren't sales
+    pub gifted: u32,
+    /// Most copies that can ever be minted, unlimited when `None`
+    pub max_supply: Option<u64>,
+    /// Cap the creator first announced, `max_supply` can never be raised above it
+    pub announced_supply: Option<u64>,
+    /// Copies minted so far, sold and gifted
+    pub minted: u64,
+    /// Most copies a single buyer can purchase, unlimited when `None`
+    pub max_per_wallet: Option<u32>,
+    /// Hex encoded sha256 merkle root of the addresses allowed to buy, anyone can when
+    /// `None`
+    pub merkle_root: Option<String>,
+    /// Seconds a shopper can get a refund for after buying, sale proceeds and tokens are
+    /// held in escrow until it closes. No refunds when `None`.
+    pub refund_window: Option<u64>,
+    /// Seconds of access each mint or renewal buys, the collection sells one-off
+    /// products when `None`
+    pub subscription_period: Option<u64>,
+    /// Set by the creator to stop sales until the collection is resumed
+    pub paused: bool,
     pub collection_address: Option<Addr>,
 }
 
@@ -24,7 +197,32 @@ pub struct CollectionParams {
     pub code_id: u64,
     pub name: String,
     pub symbol: String,
-    pub mint_fee: u128,
+    /// Accepted payment denoms, each with its own mint fee. Unused by dutch auctions and
+    /// USD prices.
+    pub prices: Vec<Price>,
+    /// Fixed prices when `None`
+    pub pricing_mode: Option<PricingMode>,
+    /// Ordered sale phases, only for fixed prices. Mints are open at any time when `None`.
+    pub phases: Option<Vec<SalePhase>>,
+    /// Bulk discounts, none by default
+    pub quantity_tiers: Option<Vec<QuantityTier>>,
+    /// Creator share payees, the whole share goes to the creator when `None`
+    pub revenue_split: Option<RevenueSplit>,
+    /// Share of the creator earnings paid to the referrer of a sale, none by default
+    pub referral_rate: Option<Decimal>,
+    /// Most copies the creator can gift, unlimited when `None`
+    pub gift_allowance: Option<u32>,
+    /// Limited edition size, unlimited when `None`
+    pub max_supply: Option<u64>,
+    /// Most copies a single buyer can purchase, unlimited when `None`
+    pub max_per_wallet: Option<u32>,
+    /// Hex encoded root of the allowlist, open to anyone when `None`
+    pub merkle_root: Option<String>,
+    /// Seconds a shopper can get a refund for after buying, no refunds when `None`
+    pub refund_window: Option<u64>,
+    /// Seconds of access each mint or renewal buys, makes the collection a
+    /// subscription when set
+    pub subscription_period: Option<u64>,
 }
 
 #[cw_serde]
@@ -35,24 +233,286 @@ pub struct MintParams {
     pub code_id: u64,
     pub owner: String,
     pub token_uri: String,
+    /// Promoter who brought in the shopper
+    pub referrer: Option<Addr>,
+    /// Coupon code, the contract only stores its sha256 hash
+    pub coupon: Option<String>,
+    /// Number of copies to mint, one by default
+    pub quantity: Option<u32>,
+    /// Most the shopper is willing to pay in total, guards against price moves
+    pub max_payment: Option<Uint128>,
+    /// Hex encoded sibling hashes from the allowlist leaf of the shopper up to the root
+    pub merkle_proof: Option<Vec<String>>,
+    /// Copies the allowlist grants the shopper, part of their leaf when set
+    pub allocation: Option<u32>,
+}
+
+#[cw_serde]
+pub struct GiftMintParams {
+    pub collection_name: String,
+    pub recipients: Vec<String>,
+    pub token_uri: String,
+}
+
+#[cw_serde]
+pub struct RenewParams {
+    pub collection_creator: Addr,
+    pub collection_name: String,
+    pub token_id: String,
+}
+
+#[cw_serde]
+pub struct UpdatePhasesParams {
+    pub collection_name: String,
+    /// Replaces every phase, an empty list opens mints at any time
+    pub phases: Vec<SalePhase>,
+}
+
+#[cw_serde]
+pub struct UpdateMerkleRootParams {
+    pub collection_name: String,
+    /// Phase to update, the collection allowlist when `None`
+    pub phase_name: Option<String>,
+    /// Removes the allowlist when `None`
+    pub merkle_root: Option<String>,
+}
+
+#[cw_serde]
+pub struct UpdateMaxSupplyParams {
+    pub collection_name: String,
+    /// Can't be below the copies already minted or above the announced supply
+    pub max_supply: u64,
 }
 
 #[cw_serde]
 pub struct UpdateMintFeeParams {
     pub collection_name: String,
-    pub mint_fee: u128,
+    pub prices: Vec<Price>,
+}
+
+#[cw_serde]
+pub struct SchedulePricesParams {
+    pub collection_name: String,
+    /// Replaces the whole schedule, an empty list cancels every scheduled price
+    pub price_schedule: Vec<ScheduledPrice>,
+}
+
+#[cw_serde]
+pub struct UpdateRevenueSplitParams {
+    pub collection_name: String,
+    pub revenue_split: Option<RevenueSplit>,
+}
+
+#[cw_serde]
+pub struct UpdateReferralRateParams {
+    pub collection_name: String,
+    pub referral_rate: Decimal,
+}
+
+/// Discount a coupon takes off the mint fee
+#[cw_serde]
+pub enum Discount {
+    /// Fraction of the mint fee taken off, e.g. `0.3` for 30% off
+    Percent(Decimal),
+    /// Fixed amount taken off the mint fee of each copy, only applies when paying in `denom`
+    Fixed { denom: Denom, amount: Uint128 },
+}
+
+#[cw_serde]
+pub struct Coupon {
+    pub discount: Discount,
+    /// Most copies the coupon can be used for
+    pub max_redemptions: u32,
+    pub redemptions: u32,
+    pub expires: Timestamp,
+}
+
+#[cw_serde]
+pub struct CouponParams {
+    pub collection_name: String,
+    /// Hex encoded sha256 hash of the coupon code
+    pub code_hash: String,
+    pub discount: Discount,
+    pub max_redemptions: u32,
+    pub expires: Timestamp,
+}
+
+#[cw_serde]
+pub struct RemoveCouponParams {
+    pub collection_name: String,
+    /// Hex encoded sha256 hash of the coupon code
+    pub code_hash: String,
+}
+
+#[cw_serde]
+pub struct SetCreatorFeeParams {
+    pub creator: String,
+    /// Platform fee for every collection of the creator, `None` removes the override
+    pub mint_percent: Option<Decimal>,
+}
+
+#[cw_serde]
+pub struct SetCollectionFeeParams {
+    pub creator: String,
+    pub collection_name: String,
+    /// Platform fee for the collection, `None` removes the override
+    pub mint_percent: Option<Decimal>,
 }
 
 #[cw_serde]
 pub struct WithdrawParams {
+    /// Amount of `Config.denom` to withdraw
     pub withdraw_amount: u128,
     pub withdraw_address: Addr,
+    /// Amounts of other native denoms to withdraw
+    pub coins: Option<Vec<Coin>>,
+    /// Amounts of CW20 tokens to withdraw
+    pub cw20_coins: Option<Vec<Cw20Coin>>,
+    /// Withdraws every available platform fee in every denom, ignoring the amounts
+    pub withdraw_all: Option<bool>,
 }
 
 pub type CreatorAddress = Addr;
 pub const CREATOR_COLLECTIONS: Map<(&CreatorAddress, &str), CollectionInfo> =
     Map::new("creator_collections");
 
+/// Platform fees negotiated with creators, replacing `Config.mint_percent`
+pub const CREATOR_FEE_OVERRIDES: Map<&CreatorAddress, Decimal> = Map::new("creator_fee_overrides");
+
+/// Platform fees for single collections, keyed by creator and collection name. These take
+/// precedence over creator overrides.
+pub const COLLECTION_FEE_OVERRIDES: Map<(&CreatorAddress, &str), Decimal> =
+    Map::new("collection_fee_overrides");
+
+/// Copies each buyer has purchased, keyed by creator, collection name and buyer
+pub const WALLET_MINTS: Map<(&CreatorAddress, &str, &Addr), u32> = Map::new("wallet_mints");
+
+/// Copies each buyer has purchased in a sale phase, keyed by creator, collection name,
+/// phase name and buyer
+pub const PHASE_WALLET_MINTS: Map<((&CreatorAddress, &str), &str, &Addr), u32> =
+    Map::new("phase_wallet_mints");
+
+/// Coupons keyed by creator, collection name and the hex sha256 hash of the code
+pub const COUPONS: Map<(&CreatorAddress, &str, &str), Coupon> = Map::new("coupons");
+
+/// Earnings creators can claim, keyed by creator and `denom_key`
+pub const CREATOR_BALANCES: Map<(&CreatorAddress, &str), Uint128> = Map::new("creator_balances");
+
+/// Lifetime tips received by a creator, keyed by creator and `denom_key`
+pub const CREATOR_TIPS: Map<(&CreatorAddress, &str), Uint128> = Map::new("creator_tips");
+
+/// Lifetime tips received on a collection, keyed by creator, collection name and
+/// `denom_key`
+pub const COLLECTION_TIPS: Map<(&CreatorAddress, &str, &str), Uint128> =
+    Map::new("collection_tips");
+
+/// Lifetime referral commissions, keyed by referrer and `denom_key`
+pub const REFERRAL_EARNINGS: Map<(&Addr, &str), Uint128> = Map::new("referral_earnings");
+
+/// An amount owed to an address out of a sale
+#[cw_serde]
+pub struct Payout {
+    pub address: Addr,
+    pub amount: Uint128,
+}
+
+/// How a mint fee is divided once the sale is final
+#[cw_serde]
+pub struct SaleProceeds {
+    pub denom: Denom,
+    pub platform_amount: Uint128,
+    pub referral: Option<Payout>,
+    /// Creator share, split among the revenue split payees
+    pub payouts: Vec<Payout>,
+}
+
+/// Sale held in the contract until its refund window closes, the factory owns its tokens
+/// until then
+#[cw_serde]
+pub struct Escrow {
+    pub buyer: Addr,
+    pub collection_address: Addr,
+    /// Every copy bought in the sale, a refund covers all of them
+    pub token_ids: Vec<String>,
+    /// Mint fee the buyer paid, returned in full on a refund
+    pub amount: Uint128,
+    pub proceeds: SaleProceeds,
+    pub refundable_until: Timestamp,
+}
+
+/// Escrowed sales keyed by collection creator and escrow id
+pub const ESCROWS: Map<(&CreatorAddress, u64), Escrow> = Map::new("escrows");
+
+/// Escrow ids of each creator ordered by the end of their refund window in nanoseconds,
+/// keyed by collection creator, `refundable_until` and escrow id
+pub const ESCROW_EXPIRIES: Map<(&CreatorAddress, u64, u64), Empty> = Map::new("escrow_expiries");
+
+/// Escrow key of every escrowed token, keyed by collection address and token id
+pub const TOKEN_ESCROWS: Map<(&Addr, &str), (CreatorAddress, u64)> = Map::new("token_escrows");
+
+/// Time each refunded token was refunded at, keyed by collection address and token id
+pub const REFUNDED_TOKENS: Map<(&Addr, &str), Timestamp> = Map::new("refunded_tokens");
+
+pub const ESCROW_ID: Item<u64> = Item::new("escrow_id");
+
+pub fn increment_escrow_id(store: &mut dyn Storage) -> StdResult<u64> {
+    let val = ESCROW_ID.may_load(store)?.unwrap_or_default() + 1;
+    ESCROW_ID.save(store, &val)?;
+    Ok(val)
+}
+
+#[cw_serde]
+pub struct Subscription {
+    pub owner: Addr,
+    pub expires: Timestamp,
+}
+
+/// Subscription tokens keyed by collection address and token id
+pub const SUBSCRIPTIONS: Map<(&Addr, &str), Subscription> = Map::new("subscriptions");
+
+/// Latest expiry of the subscription tokens an owner holds, keyed by collection address
+/// and owner
+pub const SUBSCRIBER_EXPIRIES: Map<(&Addr, &Addr), Timestamp> = Map::new("subscriber_expiries");
+
+/// Expiry of each subscription token of an owner, keyed by collection address, owner and
+/// token id. Lets the latest expiry be worked out again when a token is refunded.
+pub const SUBSCRIBER_TOKENS: Map<(&Addr, &Addr, &str), Timestamp> = Map::new("subscriber_tokens");
+
+/// Platform share of sales in a single denom
+#[cw_serde]
+#[derive(Default)]
+pub struct PlatformFees {
+    pub accrued: Uint128,
+    pub withdrawn: Uint128,
+}
+
+impl PlatformFees {
+    /// Fees that have accrued but haven't been withdrawn yet
+    pub fn available(&self) -> StdResult<Uint128> {
+        Ok(self.accrued.checked_sub(self.withdrawn)?)
+    }
+}
+
+/// Platform fees keyed by `denom_key`
+pub const PLATFORM_FEES: Map<&str, PlatformFees> = Map::new("platform_fees");
+
+/// Storage key for a payment denom. CW20 tokens get their own prefix so they can never
+/// collide with a native denom.
+pub fn denom_key(denom: &Denom) -> String {
+    match denom {
+        Denom::Native(denom) => format!("native:{denom}"),
+        Denom::Cw20(addr) => format!("cw20:{addr}"),
+    }
+}
+
+pub fn denom_from_key(key: &str) -> StdResult<Denom> {
+    match key.split_once(':') {
+        Some(("native", denom)) => Ok(Denom::Native(denom.to_string())),
+        Some(("cw20", addr)) => Ok(Denom::Cw20(Addr::unchecked(addr))),
+        _ => Err(StdError::generic_err(format!("invalid denom key: {key}"))),
+    }
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
 pub struct PendingInstantiation {
     pub creator: Addr,
@@ -1,4 +1,6 @@
 #![allow(dead_code)]
+use cosmwasm_std::Decimal;
+
 pub const ADMIN: &str = "admin";
 
 pub const ADMIN2: &str = "admin2";
@@ -11,9 +13,11 @@ pub const UNAUTHORIZED: &str = "unauthorized";
 
 pub const NATIVE_DENOM: &str = "uxion";
 
+pub const OTHER_DENOM: &str = "uusdc";
+
 pub const MOCK_MINT_FEE: u128 = 1000000;
 
-pub const MOCK_MINT_PERCENT: u128 = 2;
+pub const MOCK_MINT_PERCENT: Decimal = Decimal::percent(2);
 
 pub const MOCK_COLLECTION_NAME: &str = "product";
 
@@ -2,11 +2,14 @@
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-use cosmwasm_std::{to_json_binary, Addr, Coin, CosmosMsg, StdResult, WasmMsg};
+use cosmwasm_std::{to_json_binary, Addr, Coin, CosmosMsg, StdResult, Uint128, WasmMsg};
+use cw20::Denom;
 
 use crate::msg::ExecuteMsg;
-use crate::state::MintParams;
-use crate::testing::constants::{CREATOR, MOCK_COLLECTION_NAME, SHOPPER};
+use crate::state::{CollectionParams, MintParams, Price};
+use crate::testing::constants::{
+    CREATOR, MOCK_COLLECTION_NAME, MOCK_COLLECTION_SYMBOL, MOCK_MINT_FEE, NATIVE_DENOM, SHOPPER,
+};
 
 /// MintyplexContract is a wrapper around Addr that provides a lot of helpers
 /// for working with this.
@@ -26,9 +29,42 @@ impl MintyplexContract {
             code_id,
             owner: SHOPPER.to_string(),
             token_uri: "0".to_string(),
+            referrer: None,
+            coupon: None,
+            quantity: None,
+            max_payment: None,
+            merkle_proof: None,
+            allocation: None,
         }
     }
 
+    pub fn get_mock_collection_params(code_id: u64) -> CollectionParams {
+        CollectionParams {
+            code_id,
+            name: MOCK_COLLECTION_NAME.to_string(),
+            symbol: MOCK_COLLECTION_SYMBOL.to_string(),
+            prices: Self::get_mock_prices(),
+            pricing_mode: None,
+            phases: None,
+            quantity_tiers: None,
+            revenue_split: None,
+            referral_rate: None,
+            gift_allowance: None,
+            max_supply: None,
+            max_per_wallet: None,
+            merkle_root: None,
+            refund_window: None,
+            subscription_period: None,
+        }
+    }
+
+    pub fn get_mock_prices() -> Vec<Price> {
+        vec![Price {
+            denom: Denom::Native(NATIVE_DENOM.to_string()),
+            amount: Uint128::new(MOCK_MINT_FEE),
+        }]
+    }
+
     pub fn call<T: Into<ExecuteMsg>>(&self, msg: T) -> StdResult<CosmosMsg> {
         let msg = to_json_binary(&msg.into())?;
         Ok(WasmMsg::Execute {
@@ -1,21 +1,39 @@
 #[cfg(test)]
 mod tests {
-    use crate::msg::{ExecuteMsg, InstantiateMsg, QueryMsg};
+    use crate::contract::{MAX_MINT_PERCENT, MAX_MINT_QUANTITY};
+    use crate::msg::{
+        AuctionPriceResponse, CreatorBalanceResponse, CurrentPhaseResponse, DenomBalance,
+        EscrowResponse, ExecuteMsg, FeeSource, InstantiateMsg, MintAllowanceResponse,
+        MintPercentResponse, PlatformFeesResponse, PriceScheduleResponse, QueryMsg, ReceiveMsg,
+        ReferralEarningsResponse, SubscriptionStatusResponse, TipsResponse,
+    };
     use crate::state::{
-        CollectionInfo, CollectionParams, Config, UpdateMintFeeParams, WithdrawParams,
+        CollectionInfo, CollectionParams, Config, Coupon, CouponParams, Discount, DutchAuction,
+        GiftMintParams, MintParams, Payee, Price, PriceOracle, PricingMode, QuantityTier,
+        RenewParams, RevenueSplit, SalePhase, SchedulePricesParams, ScheduledPrice,
+        SetCollectionFeeParams, SetCreatorFeeParams, UpdateMaxSupplyParams, UpdateMerkleRootParams,
+        UpdateMintFeeParams, UpdatePhasesParams, UpdateReferralRateParams,
+        UpdateRevenueSplitParams, UsdPrice, WithdrawParams,
     };
     use crate::testing::constants::{
-        ADMIN, ADMIN2, CREATOR, MOCK_COLLECTION_NAME, MOCK_COLLECTION_SYMBOL, MOCK_MINT_FEE,
-        MOCK_MINT_PERCENT, NATIVE_DENOM, SHOPPER, UNAUTHORIZED,
+        ADMIN, ADMIN2, CREATOR, MOCK_COLLECTION_NAME, MOCK_MINT_FEE, MOCK_MINT_PERCENT,
+        NATIVE_DENOM, OTHER_DENOM, SHOPPER, UNAUTHORIZED,
     };
     use crate::testing::helpers::MintyplexContract;
+    use crate::testing::mock_oracle::{mock_oracle_contract, MockOracleExecuteMsg};
     use crate::testing::types::{
         Cw721MockExecuteMsg, Cw721MockInstantiateMsg, Cw721MockQueryMsg, MockResponse,
     };
+    use crate::ContractError;
+    use cosmwasm_std::testing::mock_env;
     use cosmwasm_std::{
-        coin, to_json_binary, Addr, Binary, Coin, Empty, Response, StdResult, Uint128,
+        coin, to_json_binary, Addr, Binary, Coin, Decimal, Empty, Response, StdResult, Uint128,
     };
-    use cw_multi_test::{App, AppBuilder, Contract, ContractWrapper, Executor};
+    use cw20::{BalanceResponse, Cw20Coin, Cw20ExecuteMsg, Cw20QueryMsg, Cw20ReceiveMsg, Denom};
+    use cw721::OwnerOfResponse;
+    use cw_multi_test::error::AnyResult;
+    use cw_multi_test::{App, AppBuilder, AppResponse, Contract, ContractWrapper, Executor};
+    use sha2::{Digest, Sha256};
 
     pub fn mintyplex_contract() -> Box<dyn Contract<Empty>> {
         let contract = ContractWrapper::new(
@@ -36,12 +54,30 @@ mod tests {
                 Ok(Response::default())
             },
             |_, _, _msg: Cw721MockQueryMsg| -> StdResult<Binary> {
-                Ok(to_json_binary(&MockResponse {})?)
+                to_json_binary(&MockResponse {})
             },
         );
         Box::new(contract)
     }
 
+    pub fn cw721_non_transferable_contract() -> Box<dyn Contract<Empty>> {
+        let contract = ContractWrapper::new(
+            cw721_non_transferable::entry::execute,
+            cw721_non_transferable::entry::instantiate,
+            cw721_non_transferable::entry::query,
+        );
+        Box::new(contract)
+    }
+
+    pub fn cw20_contract() -> Box<dyn Contract<Empty>> {
+        let contract = ContractWrapper::new(
+            cw20_base::contract::execute,
+            cw20_base::contract::instantiate,
+            cw20_base::contract::query,
+        );
+        Box::new(contract)
+    }
+
     pub fn get_collection_info(
         app: &App,
         contract: &MintyplexContract,
@@ -61,10 +97,16 @@ mod tests {
                 .init_balance(
                     storage,
                     &Addr::unchecked(SHOPPER),
-                    vec![Coin {
-                        denom: NATIVE_DENOM.to_string(),
-                        amount: Uint128::new(100000000),
-                    }],
+                    vec![
+                        Coin {
+                            denom: NATIVE_DENOM.to_string(),
+                            amount: Uint128::new(100000000),
+                        },
+                        Coin {
+                            denom: OTHER_DENOM.to_string(),
+                            amount: Uint128::new(100000000),
+                        },
+                    ],
                 )
                 .unwrap();
         })
@@ -78,6 +120,9 @@ mod tests {
         let msg = InstantiateMsg {
             owner: Some(ADMIN.to_string()),
             mint_percent: MOCK_MINT_PERCENT,
+            denom: NATIVE_DENOM.to_string(),
+            price_oracle: None,
+            charge_tip_fee: false,
         };
         let mintyplex_contract_addr = app
             .instantiate_contract(
@@ -100,18 +145,132 @@ mod tests {
 
         let cw721_code_id = app.store_code(cw721_contract());
 
+        let collection_params = MintyplexContract::get_mock_collection_params(cw721_code_id);
+
+        let msg = ExecuteMsg::CreateCollection(collection_params);
+        let cosmos_msg = mintyplex_contract.call(msg).unwrap();
+        app.execute(Addr::unchecked(CREATOR), cosmos_msg).unwrap();
+
+        (app, mintyplex_contract)
+    }
+
+    /// Creates the mock collection with custom params
+    fn app_with_collection_params(
+        update_params: impl FnOnce(&mut CollectionParams),
+    ) -> (App, MintyplexContract) {
+        let (mut app, mintyplex_contract) = proper_instantiate();
+
+        let cw721_code_id = app.store_code(cw721_contract());
+
+        let mut collection_params = MintyplexContract::get_mock_collection_params(cw721_code_id);
+        update_params(&mut collection_params);
+
+        let msg = ExecuteMsg::CreateCollection(collection_params);
+        let cosmos_msg = mintyplex_contract.call(msg).unwrap();
+        app.execute(Addr::unchecked(CREATOR), cosmos_msg).unwrap();
+
+        (app, mintyplex_contract)
+    }
+
+    /// Mints the mock collection as the shopper, paying `funds`
+    fn mint(
+        app: &mut App,
+        contract: &MintyplexContract,
+        funds: Vec<Coin>,
+    ) -> AnyResult<AppResponse> {
+        let collection_info = get_collection_info(app, contract).unwrap();
+        let mint_params =
+            MintyplexContract::get_mock_mint_params(collection_info.collection_address.unwrap(), 0);
+
+        let cosmos_msg = contract
+            .call_with_funds(ExecuteMsg::MintNFT(mint_params), funds)
+            .unwrap();
+        app.execute(Addr::unchecked(SHOPPER), cosmos_msg)
+    }
+
+    /// Instantiates a CW20 token funded for the shopper and a collection priced in it
+    fn app_with_cw20_collection() -> (App, MintyplexContract, Addr) {
+        let (mut app, mintyplex_contract) = proper_instantiate();
+
+        let cw20_code_id = app.store_code(cw20_contract());
+        let cw20_addr = app
+            .instantiate_contract(
+                cw20_code_id,
+                Addr::unchecked(ADMIN),
+                &cw20_base::msg::InstantiateMsg {
+                    name: "Mock USD".to_string(),
+                    symbol: "MUSD".to_string(),
+                    decimals: 6,
+                    initial_balances: vec![Cw20Coin {
+                        address: SHOPPER.to_string(),
+                        amount: Uint128::new(100000000),
+                    }],
+                    mint: None,
+                    marketing: None,
+                },
+                &[],
+                "cw20",
+                None,
+            )
+            .unwrap();
+
+        let cw721_code_id = app.store_code(cw721_contract());
+
         let collection_params = CollectionParams {
-            code_id: cw721_code_id,
-            name: MOCK_COLLECTION_NAME.to_string(),
-            symbol: MOCK_COLLECTION_SYMBOL.to_string(),
-            mint_fee: MOCK_MINT_FEE,
+            prices: vec![Price {
+                denom: Denom::Cw20(cw20_addr.clone()),
+                amount: Uint128::new(MOCK_MINT_FEE),
+            }],
+            ..MintyplexContract::get_mock_collection_params(cw721_code_id)
         };
 
         let msg = ExecuteMsg::CreateCollection(collection_params);
         let cosmos_msg = mintyplex_contract.call(msg).unwrap();
         app.execute(Addr::unchecked(CREATOR), cosmos_msg).unwrap();
 
-        (app, mintyplex_contract)
+        (app, mintyplex_contract, cw20_addr)
+    }
+
+    pub fn get_creator_balance(
+        app: &App,
+        contract: &MintyplexContract,
+    ) -> StdResult<CreatorBalanceResponse> {
+        let query_msg = QueryMsg::CreatorBalance {
+            creator: Addr::unchecked(CREATOR),
+        };
+
+        app.wrap().query_wasm_smart(contract.addr(), &query_msg)
+    }
+
+    pub fn get_platform_fees(
+        app: &App,
+        contract: &MintyplexContract,
+    ) -> StdResult<PlatformFeesResponse> {
+        let query_msg = QueryMsg::PlatformFees {
+            denom: Denom::Native(NATIVE_DENOM.to_string()),
+        };
+
+        app.wrap().query_wasm_smart(contract.addr(), &query_msg)
+    }
+
+    fn claim_earnings(app: &mut App, contract: &MintyplexContract) -> AnyResult<AppResponse> {
+        let cosmos_msg = contract
+            .call(ExecuteMsg::ClaimEarnings { denoms: None })
+            .unwrap();
+        app.execute(Addr::unchecked(CREATOR), cosmos_msg)
+    }
+
+    fn cw20_balance(app: &App, token: &Addr, address: &str) -> Uint128 {
+        let res: BalanceResponse = app
+            .wrap()
+            .query_wasm_smart(
+                token,
+                &Cw20QueryMsg::Balance {
+                    address: address.to_string(),
+                },
+            )
+            .unwrap();
+        res.balance
     }
 
     fn app_with_mint() -> (App, MintyplexContract) {
@@ -130,7 +289,7 @@ mod tests {
         let msg = ExecuteMsg::MintNFT(mint_params);
 
         let cosmos_msg = mintyplex_contract_with_collection
-            .call_with_funds(msg, vec![coin(1000000, "uxion")])
+            .call_with_funds(msg, vec![coin(1000000, NATIVE_DENOM)])
             .unwrap();
 
         app.execute(Addr::unchecked(SHOPPER), cosmos_msg).unwrap();
@@ -147,12 +306,7 @@ mod tests {
 
             let cw721_code_id = app.store_code(cw721_contract());
 
-            let collection_params = CollectionParams {
-                code_id: cw721_code_id,
-                name: MOCK_COLLECTION_NAME.to_string(),
-                symbol: MOCK_COLLECTION_SYMBOL.to_string(),
-                mint_fee: MOCK_MINT_FEE,
-            };
+            let collection_params = MintyplexContract::get_mock_collection_params(cw721_code_id);
 
             let msg = ExecuteMsg::CreateCollection(collection_params);
             let cosmos_msg = mintyplex_contract.call(msg).unwrap();
@@ -180,7 +334,7 @@ mod tests {
 
             // test if shopper passes the wrong amount
             let cosmos_msg_with_wrong_amount = mintyplex_contract_with_collection
-                .call_with_funds(msg.clone(), vec![coin(100000, "uxion")])
+                .call_with_funds(msg.clone(), vec![coin(100000, NATIVE_DENOM)])
                 .unwrap();
 
             let err_res = app
@@ -202,11 +356,311 @@ mod tests {
 
             // when shopper passes the right amount and right denom
             let cosmos_msg = mintyplex_contract_with_collection
-                .call_with_funds(msg, vec![coin(MOCK_MINT_FEE, "uxion")])
+                .call_with_funds(msg, vec![coin(MOCK_MINT_FEE, NATIVE_DENOM)])
                 .unwrap();
             let res = app.execute(Addr::unchecked(SHOPPER), cosmos_msg).is_ok();
             assert!(res);
         }
+        #[test]
+        fn test_mint_nft_payment_validation() {
+            let (mut app, mintyplex_contract_with_collection) = app_with_collection();
+
+            let collection_info =
+                get_collection_info(&app, &mintyplex_contract_with_collection).unwrap();
+            let msg = ExecuteMsg::MintNFT(MintyplexContract::get_mock_mint_params(
+                collection_info.collection_address.unwrap(),
+                0,
+            ));
+
+            let mut mint_with_funds = |funds: Vec<Coin>| {
+                let cosmos_msg = mintyplex_contract_with_collection
+                    .call_with_funds(msg.clone(), funds)
+                    .unwrap();
+                app.execute(Addr::unchecked(SHOPPER), cosmos_msg)
+            };
+
+            let err = mint_with_funds(vec![]).unwrap_err();
+            assert_eq!(
+                err.downcast::<ContractError>().unwrap(),
+                ContractError::NoFunds {}
+            );
+
+            let err = mint_with_funds(vec![
+                coin(MOCK_MINT_FEE, NATIVE_DENOM),
+                coin(MOCK_MINT_FEE, OTHER_DENOM),
+            ])
+            .unwrap_err();
+            assert_eq!(
+                err.downcast::<ContractError>().unwrap(),
+                ContractError::MultipleDenoms {}
+            );
+
+            let err = mint_with_funds(vec![coin(MOCK_MINT_FEE, OTHER_DENOM)]).unwrap_err();
+            assert_eq!(
+                err.downcast::<ContractError>().unwrap(),
+                ContractError::UnacceptedDenom {
+                    denom: OTHER_DENOM.to_string()
+                }
+            );
+
+            let err = mint_with_funds(vec![coin(MOCK_MINT_FEE - 1, NATIVE_DENOM)]).unwrap_err();
+            assert_eq!(
+                err.downcast::<ContractError>().unwrap(),
+                ContractError::InsufficientFunds {
+                    denom: NATIVE_DENOM.to_string(),
+                    expected: Uint128::new(MOCK_MINT_FEE),
+                    received: Uint128::new(MOCK_MINT_FEE - 1),
+                }
+            );
+
+            // overpayments are refunded to the shopper
+            mint_with_funds(vec![coin(MOCK_MINT_FEE + 500, NATIVE_DENOM)]).unwrap();
+
+            assert_eq!(
+                app.wrap()
+                    .query_balance(Addr::unchecked(SHOPPER), NATIVE_DENOM)
+                    .unwrap()
+                    .amount,
+                Uint128::new(100000000 - MOCK_MINT_FEE)
+            );
+        }
+
+        #[test]
+        fn test_mint_nft_with_cw20() {
+            let (mut app, mintyplex_contract, cw20_addr) = app_with_cw20_collection();
+
+            let collection_info = get_collection_info(&app, &mintyplex_contract).unwrap();
+            assert_eq!(
+                collection_info.prices[0].denom,
+                Denom::Cw20(cw20_addr.clone())
+            );
+
+            let mint_params = MintyplexContract::get_mock_mint_params(
+                collection_info.collection_address.unwrap(),
+                0,
+            );
+
+            // native funds are rejected for collections priced in a CW20 token
+            let cosmos_msg = mintyplex_contract
+                .call_with_funds(
+                    ExecuteMsg::MintNFT(mint_params.clone()),
+                    vec![coin(MOCK_MINT_FEE, NATIVE_DENOM)],
+                )
+                .unwrap();
+            assert!(app.execute(Addr::unchecked(SHOPPER), cosmos_msg).is_err());
+
+            // test if shopper sends the wrong amount
+            let send_msg = Cw20ExecuteMsg::Send {
+                contract: mintyplex_contract.addr().to_string(),
+                amount: Uint128::new(100000),
+                msg: to_json_binary(&ReceiveMsg::MintNFT(mint_params.clone())).unwrap(),
+            };
+            assert!(app
+                .execute_contract(Addr::unchecked(SHOPPER), cw20_addr.clone(), &send_msg, &[])
+                .is_err());
+
+            let send_msg = Cw20ExecuteMsg::Send {
+                contract: mintyplex_contract.addr().to_string(),
+                amount: Uint128::new(MOCK_MINT_FEE),
+                msg: to_json_binary(&ReceiveMsg::MintNFT(mint_params)).unwrap(),
+            };
+            app.execute_contract(Addr::unchecked(SHOPPER), cw20_addr.clone(), &send_msg, &[])
+                .unwrap();
+
+            claim_earnings(&mut app, &mintyplex_contract).unwrap();
+
+            let mintyplex_amount = Uint128::new(MOCK_MINT_FEE).mul_floor(MOCK_MINT_PERCENT);
+            assert_eq!(
+                cw20_balance(&app, &cw20_addr, CREATOR),
+                Uint128::new(MOCK_MINT_FEE) - mintyplex_amount
+            );
+            assert_eq!(
+                cw20_balance(&app, &cw20_addr, mintyplex_contract.addr().as_str()),
+                mintyplex_amount
+            );
+        }
+
+        #[test]
+        fn test_cw20_tip_and_renew() {
+            let (mut app, mintyplex_contract, cw20_addr) = app_with_cw20_collection();
+            let cw20_denom = Denom::Cw20(cw20_addr.clone());
+
+            let tip_msg = ReceiveMsg::Tip {
+                creator: CREATOR.to_string(),
+                collection_name: MOCK_COLLECTION_NAME.to_string(),
+                memo: None,
+            };
+            let send_msg = Cw20ExecuteMsg::Send {
+                contract: mintyplex_contract.addr().to_string(),
+                amount: Uint128::new(MOCK_MINT_FEE),
+                msg: to_json_binary(&tip_msg).unwrap(),
+            };
+            app.execute_contract(Addr::unchecked(SHOPPER), cw20_addr.clone(), &send_msg, &[])
+                .unwrap();
+
+            // should fail to tip in a token the collection isn't priced in
+            let err = app
+                .execute_contract(
+                    Addr::unchecked(UNAUTHORIZED),
+                    mintyplex_contract.addr(),
+                    &ExecuteMsg::Receive(Cw20ReceiveMsg {
+                        sender: UNAUTHORIZED.to_string(),
+                        amount: Uint128::one(),
+                        msg: to_json_binary(&tip_msg).unwrap(),
+                    }),
+                    &[],
+                )
+                .unwrap_err();
+            assert_eq!(
+                err.downcast::<ContractError>().unwrap(),
+                ContractError::UnacceptedDenom {
+                    denom: UNAUTHORIZED.to_string(),
+                }
+            );
+
+            // should fail when none of the claimed denoms has a balance
+            let cosmos_msg = mintyplex_contract
+                .call(ExecuteMsg::ClaimEarnings {
+                    denoms: Some(vec![Denom::Native(NATIVE_DENOM.to_string())]),
+                })
+                .unwrap();
+            let err = app
+                .execute(Addr::unchecked(CREATOR), cosmos_msg)
+                .unwrap_err();
+            assert_eq!(
+                err.downcast::<ContractError>().unwrap(),
+                ContractError::NothingToClaim {}
+            );
+
+            // a denom listed twice is only paid out once
+            let cosmos_msg = mintyplex_contract
+                .call(ExecuteMsg::ClaimEarnings {
+                    denoms: Some(vec![cw20_denom.clone(), cw20_denom.clone()]),
+                })
+                .unwrap();
+            app.execute(Addr::unchecked(CREATOR), cosmos_msg).unwrap();
+            assert_eq!(
+                cw20_balance(&app, &cw20_addr, CREATOR),
+                Uint128::new(MOCK_MINT_FEE)
+            );
+
+            // subscriptions can be bought and renewed with CW20 tokens
+            let cw721_code_id = app.store_code(cw721_contract());
+            let cosmos_msg = mintyplex_contract
+                .call(ExecuteMsg::CreateCollection(CollectionParams {
+                    name: "membership".to_string(),
+                    prices: vec![Price {
+                        denom: cw20_denom,
+                        amount: Uint128::new(MOCK_MINT_FEE),
+                    }],
+                    subscription_period: Some(1000),
+                    ..MintyplexContract::get_mock_collection_params(cw721_code_id)
+                }))
+                .unwrap();
+            app.execute(Addr::unchecked(CREATOR), cosmos_msg).unwrap();
+
+            let collection_info: CollectionInfo = app
+                .wrap()
+                .query_wasm_smart(
+                    mintyplex_contract.addr(),
+                    &QueryMsg::CreatorCollections {
+                        creator: Addr::unchecked(CREATOR),
+                        collection_name: "membership".to_string(),
+                    },
+                )
+                .unwrap();
+            let collection_address = collection_info.collection_address.unwrap();
+            let mint_params = MintParams {
+                collection_name: "membership".to_string(),
+                ..MintyplexContract::get_mock_mint_params(collection_address.clone(), 0)
+            };
+
+            let minted_at = app.block_info().time;
+            let receive_msgs = [
+                ReceiveMsg::MintNFT(mint_params),
+                ReceiveMsg::Renew(RenewParams {
+                    collection_creator: Addr::unchecked(CREATOR),
+                    collection_name: "membership".to_string(),
+                    token_id: "1".to_string(),
+                }),
+            ];
+            for receive_msg in receive_msgs {
+                let send_msg = Cw20ExecuteMsg::Send {
+                    contract: mintyplex_contract.addr().to_string(),
+                    amount: Uint128::new(MOCK_MINT_FEE),
+                    msg: to_json_binary(&receive_msg).unwrap(),
+                };
+                app.execute_contract(Addr::unchecked(SHOPPER), cw20_addr.clone(), &send_msg, &[])
+                    .unwrap();
+            }
+
+            let status: SubscriptionStatusResponse = app
+                .wrap()
+                .query_wasm_smart(
+                    mintyplex_contract.addr(),
+                    &QueryMsg::IsSubscriptionActive {
+                        owner: SHOPPER.to_string(),
+                        collection: collection_address.to_string(),
+                    },
+                )
+                .unwrap();
+            assert_eq!(status.expires, Some(minted_at.plus_seconds(2000)));
+        }
+
+        #[test]
+        fn test_claim_earnings() {
+            let (mut app, mintyplex_contract_with_mint) = app_with_mint();
+
+            let creator_amount = Uint128::new(MOCK_MINT_FEE)
+                - Uint128::new(MOCK_MINT_FEE).mul_floor(MOCK_MINT_PERCENT);
+
+            // sales are credited to the creator instead of being sent right away
+            assert!(app
+                .wrap()
+                .query_balance(Addr::unchecked(CREATOR), NATIVE_DENOM)
+                .unwrap()
+                .amount
+                .is_zero());
+            assert_eq!(
+                get_creator_balance(&app, &mintyplex_contract_with_mint).unwrap(),
+                CreatorBalanceResponse {
+                    balances: vec![DenomBalance {
+                        denom: Denom::Native(NATIVE_DENOM.to_string()),
+                        amount: creator_amount,
+                    }],
+                }
+            );
+
+            // should fail for an address with nothing to claim
+            let cosmos_msg = mintyplex_contract_with_mint
+                .call(ExecuteMsg::ClaimEarnings { denoms: None })
+                .unwrap();
+            let err = app
+                .execute(Addr::unchecked(UNAUTHORIZED), cosmos_msg)
+                .unwrap_err();
+            assert_eq!(
+                err.downcast::<ContractError>().unwrap(),
+                ContractError::NothingToClaim {}
+            );
+
+            claim_earnings(&mut app, &mintyplex_contract_with_mint).unwrap();
+
+            assert_eq!(
+                app.wrap()
+                    .query_balance(Addr::unchecked(CREATOR), NATIVE_DENOM)
+                    .unwrap()
+                    .amount,
+                creator_amount
+            );
+            assert!(get_creator_balance(&app, &mintyplex_contract_with_mint)
+                .unwrap()
+                .balances
+                .is_empty());
+
+            // balances can only be claimed once
+            assert!(claim_earnings(&mut app, &mintyplex_contract_with_mint).is_err());
+        }
+
         #[test]
         fn test_withdraw() {
             let (mut app, mintyplex_contract_with_mint) = app_with_mint();
@@ -224,9 +678,44 @@ mod tests {
                 .unwrap()
                 .amount;
 
+            let mintyplex_amount = Uint128::new(MOCK_MINT_FEE).mul_floor(MOCK_MINT_PERCENT);
+            let platform_fees = get_platform_fees(&app, &mintyplex_contract_with_mint).unwrap();
+            assert_eq!(
+                platform_fees,
+                PlatformFeesResponse {
+                    denom: Denom::Native(NATIVE_DENOM.to_string()),
+                    accrued: mintyplex_amount,
+                    withdrawn: Uint128::zero(),
+                    available: mintyplex_amount,
+                }
+            );
+
+            // should fail when withdrawing funds owed to the creator
+            let cosmos_msg = mintyplex_contract_with_mint
+                .call(ExecuteMsg::Withdraw(WithdrawParams {
+                    withdraw_amount: contract_balance.u128(),
+                    withdraw_address: Addr::unchecked(ADMIN),
+                    coins: None,
+                    cw20_coins: None,
+                    withdraw_all: None,
+                }))
+                .unwrap();
+            let err = app.execute(Addr::unchecked(ADMIN), cosmos_msg).unwrap_err();
+            assert_eq!(
+                err.downcast::<ContractError>().unwrap(),
+                ContractError::InsufficientPlatformFees {
+                    denom: NATIVE_DENOM.to_string(),
+                    available: mintyplex_amount,
+                    requested: contract_balance,
+                }
+            );
+
             let withdraw_params = WithdrawParams {
-                withdraw_amount: u128::from(contract_balance),
+                withdraw_amount: mintyplex_amount.u128(),
                 withdraw_address: Addr::unchecked(ADMIN),
+                coins: None,
+                cw20_coins: None,
+                withdraw_all: None,
             };
 
             let msg = ExecuteMsg::Withdraw(withdraw_params);
@@ -239,14 +728,189 @@ mod tests {
                 .is_err();
             assert!(res_err);
 
-            let _ = app.execute(Addr::unchecked(ADMIN), cosmos_msg);
+            app.execute(Addr::unchecked(ADMIN), cosmos_msg.clone())
+                .unwrap();
+
+            assert_eq!(
+                app.wrap()
+                    .query_balance(Addr::unchecked(ADMIN), NATIVE_DENOM)
+                    .unwrap()
+                    .amount,
+                mintyplex_amount
+            );
+
+            let platform_fees = get_platform_fees(&app, &mintyplex_contract_with_mint).unwrap();
+            assert_eq!(platform_fees.withdrawn, mintyplex_amount);
+            assert!(platform_fees.available.is_zero());
+
+            // platform fees can only be withdrawn once
+            assert!(app.execute(Addr::unchecked(ADMIN), cosmos_msg).is_err());
+
+            // the creator can still claim their share
+            claim_earnings(&mut app, &mintyplex_contract_with_mint).unwrap();
+        }
+
+        #[test]
+        fn test_withdraw_multi_asset() {
+            let (mut app, mintyplex_contract, cw20_addr) = app_with_cw20_collection();
+
+            let mut prices = MintyplexContract::get_mock_prices();
+            prices.push(Price {
+                denom: Denom::Native(OTHER_DENOM.to_string()),
+                amount: Uint128::new(MOCK_MINT_FEE),
+            });
+            prices.push(Price {
+                denom: Denom::Cw20(cw20_addr.clone()),
+                amount: Uint128::new(MOCK_MINT_FEE),
+            });
+            let cosmos_msg = mintyplex_contract
+                .call(ExecuteMsg::UpdateMintFee(UpdateMintFeeParams {
+                    collection_name: MOCK_COLLECTION_NAME.to_string(),
+                    prices,
+                }))
+                .unwrap();
+            app.execute(Addr::unchecked(CREATOR), cosmos_msg).unwrap();
+
+            // one sale in each denom
+            mint(
+                &mut app,
+                &mintyplex_contract,
+                vec![coin(MOCK_MINT_FEE, NATIVE_DENOM)],
+            )
+            .unwrap();
+            mint(
+                &mut app,
+                &mintyplex_contract,
+                vec![coin(MOCK_MINT_FEE, OTHER_DENOM)],
+            )
+            .unwrap();
+            let collection_info = get_collection_info(&app, &mintyplex_contract).unwrap();
+            let send_msg = Cw20ExecuteMsg::Send {
+                contract: mintyplex_contract.addr().to_string(),
+                amount: Uint128::new(MOCK_MINT_FEE),
+                msg: to_json_binary(&ReceiveMsg::MintNFT(
+                    MintyplexContract::get_mock_mint_params(
+                        collection_info.collection_address.unwrap(),
+                        0,
+                    ),
+                ))
+                .unwrap(),
+            };
+            app.execute_contract(Addr::unchecked(SHOPPER), cw20_addr.clone(), &send_msg, &[])
+                .unwrap();
+
+            let mintyplex_amount = Uint128::new(MOCK_MINT_FEE).mul_floor(MOCK_MINT_PERCENT);
+            let half = mintyplex_amount.u128() / 2;
+
+            let withdraw = |app: &mut App, params: WithdrawParams| {
+                let cosmos_msg = mintyplex_contract
+                    .call(ExecuteMsg::Withdraw(params))
+                    .unwrap();
+                app.execute(Addr::unchecked(ADMIN), cosmos_msg)
+            };
+            let params = WithdrawParams {
+                withdraw_amount: 0,
+                withdraw_address: Addr::unchecked(ADMIN),
+                coins: None,
+                cw20_coins: None,
+                withdraw_all: None,
+            };
+
+            // should fail with nothing requested
+            let err = withdraw(&mut app, params.clone()).unwrap_err();
+            assert_eq!(
+                err.downcast::<ContractError>().unwrap(),
+                ContractError::NothingToWithdraw {}
+            );
+
+            // should fail when a CW20 amount is owed to the creator
+            let err = withdraw(
+                &mut app,
+                WithdrawParams {
+                    cw20_coins: Some(vec![Cw20Coin {
+                        address: cw20_addr.to_string(),
+                        amount: mintyplex_amount + Uint128::one(),
+                    }]),
+                    ..params.clone()
+                },
+            )
+            .unwrap_err();
+            assert_eq!(
+                err.downcast::<ContractError>().unwrap(),
+                ContractError::InsufficientPlatformFees {
+                    denom: cw20_addr.to_string(),
+                    available: mintyplex_amount,
+                    requested: mintyplex_amount + Uint128::one(),
+                }
+            );
+
+            withdraw(
+                &mut app,
+                WithdrawParams {
+                    withdraw_amount: half,
+                    coins: Some(vec![coin(mintyplex_amount.u128(), OTHER_DENOM)]),
+                    cw20_coins: Some(vec![Cw20Coin {
+                        address: cw20_addr.to_string(),
+                        amount: Uint128::new(half),
+                    }]),
+                    ..params.clone()
+                },
+            )
+            .unwrap();
+
+            assert_eq!(
+                app.wrap()
+                    .query_balance(ADMIN, NATIVE_DENOM)
+                    .unwrap()
+                    .amount,
+                Uint128::new(half)
+            );
+            assert_eq!(
+                app.wrap().query_balance(ADMIN, OTHER_DENOM).unwrap().amount,
+                mintyplex_amount
+            );
+            assert_eq!(cw20_balance(&app, &cw20_addr, ADMIN), Uint128::new(half));
+
+            // sweep whatever is left
+            withdraw(
+                &mut app,
+                WithdrawParams {
+                    withdraw_all: Some(true),
+                    ..params.clone()
+                },
+            )
+            .unwrap();
 
             assert_eq!(
                 app.wrap()
-                    .query_balance(Addr::unchecked(ADMIN), "uxion")
+                    .query_balance(ADMIN, NATIVE_DENOM)
                     .unwrap()
                     .amount,
-                contract_balance
+                mintyplex_amount
+            );
+            assert_eq!(cw20_balance(&app, &cw20_addr, ADMIN), mintyplex_amount);
+            assert!(get_platform_fees(&app, &mintyplex_contract)
+                .unwrap()
+                .available
+                .is_zero());
+
+            // nothing is left to sweep but the creator can still claim their share
+            let err = withdraw(
+                &mut app,
+                WithdrawParams {
+                    withdraw_all: Some(true),
+                    ..params
+                },
+            )
+            .unwrap_err();
+            assert_eq!(
+                err.downcast::<ContractError>().unwrap(),
+                ContractError::NothingToWithdraw {}
+            );
+            claim_earnings(&mut app, &mintyplex_contract).unwrap();
+            assert_eq!(
+                cw20_balance(&app, &cw20_addr, CREATOR),
+                Uint128::new(MOCK_MINT_FEE) - mintyplex_amount
             );
         }
 
@@ -256,7 +920,11 @@ mod tests {
 
             let new_config = Config {
                 owner: Addr::unchecked(ADMIN2),
-                mint_percent: 0,
+                mint_percent: Decimal::zero(),
+                denom: NATIVE_DENOM.to_string(),
+                price_oracle: None,
+                charge_tip_fee: false,
+                paused: false,
             };
 
             let msg = ExecuteMsg::UpdateConfig(new_config.clone());
@@ -283,19 +951,2414 @@ mod tests {
         }
 
         #[test]
-        fn test_update_mint_fee() {
+        fn test_mint_nft_with_multiple_prices() {
             let (mut app, mintyplex_contract_with_collection) = app_with_collection();
 
-            let collection_info =
-                get_collection_info(&app, &mintyplex_contract_with_collection).unwrap();
+            let other_mint_fee: u128 = 500000;
 
-            assert_eq!(collection_info.mint_fee, MOCK_MINT_FEE);
+            let mut prices = MintyplexContract::get_mock_prices();
+            prices.push(Price {
+                denom: Denom::Native(OTHER_DENOM.to_string()),
+                amount: Uint128::new(other_mint_fee),
+            });
 
-            let new_mint_fee: u128 = 10;
+            let cosmos_msg = mintyplex_contract_with_collection
+                .call(ExecuteMsg::UpdateMintFee(UpdateMintFeeParams {
+                    collection_name: MOCK_COLLECTION_NAME.to_string(),
+                    prices,
+                }))
+                .unwrap();
+            app.execute(Addr::unchecked(CREATOR), cosmos_msg).unwrap();
+
+            let collection_info =
+                get_collection_info(&app, &mintyplex_contract_with_collection).unwrap();
+            let mint_params = MintyplexContract::get_mock_mint_params(
+                collection_info.collection_address.unwrap(),
+                0,
+            );
+
+            // the other denom is charged at its own price, not the native one
+            let cosmos_msg = mintyplex_contract_with_collection
+                .call_with_funds(
+                    ExecuteMsg::MintNFT(mint_params),
+                    vec![coin(MOCK_MINT_FEE, OTHER_DENOM)],
+                )
+                .unwrap();
+            app.execute(Addr::unchecked(SHOPPER), cosmos_msg).unwrap();
+
+            claim_earnings(&mut app, &mintyplex_contract_with_collection).unwrap();
+
+            let mintyplex_amount = Uint128::new(other_mint_fee).mul_floor(MOCK_MINT_PERCENT);
+            assert_eq!(
+                app.wrap()
+                    .query_balance(Addr::unchecked(CREATOR), OTHER_DENOM)
+                    .unwrap()
+                    .amount,
+                Uint128::new(other_mint_fee) - mintyplex_amount
+            );
+            assert_eq!(
+                app.wrap()
+                    .query_balance(mintyplex_contract_with_collection.addr(), OTHER_DENOM)
+                    .unwrap()
+                    .amount,
+                mintyplex_amount
+            );
+
+            // withdrawals follow the configured denom
+            let cosmos_msg = mintyplex_contract_with_collection
+                .call(ExecuteMsg::UpdateConfig(Config {
+                    owner: Addr::unchecked(ADMIN),
+                    mint_percent: MOCK_MINT_PERCENT,
+                    denom: OTHER_DENOM.to_string(),
+                    price_oracle: None,
+                    charge_tip_fee: false,
+                    paused: false,
+                }))
+                .unwrap();
+            app.execute(Addr::unchecked(ADMIN), cosmos_msg).unwrap();
+
+            let cosmos_msg = mintyplex_contract_with_collection
+                .call(ExecuteMsg::Withdraw(WithdrawParams {
+                    withdraw_amount: mintyplex_amount.u128(),
+                    withdraw_address: Addr::unchecked(ADMIN),
+                    coins: None,
+                    cw20_coins: None,
+                    withdraw_all: None,
+                }))
+                .unwrap();
+            app.execute(Addr::unchecked(ADMIN), cosmos_msg).unwrap();
+
+            assert_eq!(
+                app.wrap()
+                    .query_balance(Addr::unchecked(ADMIN), OTHER_DENOM)
+                    .unwrap()
+                    .amount,
+                mintyplex_amount
+            );
+        }
+
+        #[test]
+        fn test_update_config_denom() {
+            let (mut app, mintyplex_contract) = proper_instantiate();
+
+            // should fail with an empty denom
+            let cosmos_msg = mintyplex_contract
+                .call(ExecuteMsg::UpdateConfig(Config {
+                    owner: Addr::unchecked(ADMIN),
+                    mint_percent: MOCK_MINT_PERCENT,
+                    denom: String::new(),
+                    price_oracle: None,
+                    charge_tip_fee: false,
+                    paused: false,
+                }))
+                .unwrap();
+            assert!(app.execute(Addr::unchecked(ADMIN), cosmos_msg).is_err());
+        }
+
+        #[test]
+        fn test_mint_percent_cap() {
+            let mut app = mock_app();
+            let mintyplex_code_id = app.store_code(mintyplex_contract());
+
+            let err = app
+                .instantiate_contract(
+                    mintyplex_code_id,
+                    Addr::unchecked(ADMIN),
+                    &InstantiateMsg {
+                        owner: Some(ADMIN.to_string()),
+                        mint_percent: Decimal::percent(150),
+                        denom: NATIVE_DENOM.to_string(),
+                        price_oracle: None,
+                        charge_tip_fee: false,
+                    },
+                    &[],
+                    "test",
+                    None,
+                )
+                .unwrap_err();
+            assert_eq!(
+                err.downcast::<ContractError>().unwrap(),
+                ContractError::MintPercentTooHigh {
+                    max: MAX_MINT_PERCENT
+                }
+            );
+
+            let (mut app, mintyplex_contract) = proper_instantiate();
+
+            let cosmos_msg = mintyplex_contract
+                .call(ExecuteMsg::UpdateConfig(Config {
+                    owner: Addr::unchecked(ADMIN),
+                    mint_percent: MAX_MINT_PERCENT + Decimal::permille(1),
+                    denom: NATIVE_DENOM.to_string(),
+                    price_oracle: None,
+                    charge_tip_fee: false,
+                    paused: false,
+                }))
+                .unwrap();
+            assert!(app.execute(Addr::unchecked(ADMIN), cosmos_msg).is_err());
+        }
+
+        #[test]
+        fn test_mint_nft_fractional_mint_percent() {
+            let (mut app, mintyplex_contract_with_collection) = app_with_collection();
+
+            // 2.5% of an odd price leaves rounding dust
+            let mint_percent = Decimal::permille(25);
+            let mint_fee = Uint128::new(999);
+
+            let cosmos_msg = mintyplex_contract_with_collection
+                .call(ExecuteMsg::UpdateConfig(Config {
+                    owner: Addr::unchecked(ADMIN),
+                    mint_percent,
+                    denom: NATIVE_DENOM.to_string(),
+                    price_oracle: None,
+                    charge_tip_fee: false,
+                    paused: false,
+                }))
+                .unwrap();
+            app.execute(Addr::unchecked(ADMIN), cosmos_msg).unwrap();
+
+            let cosmos_msg = mintyplex_contract_with_collection
+                .call(ExecuteMsg::UpdateMintFee(UpdateMintFeeParams {
+                    collection_name: MOCK_COLLECTION_NAME.to_string(),
+                    prices: vec![Price {
+                        denom: Denom::Native(NATIVE_DENOM.to_string()),
+                        amount: mint_fee,
+                    }],
+                }))
+                .unwrap();
+            app.execute(Addr::unchecked(CREATOR), cosmos_msg).unwrap();
+
+            let collection_info =
+                get_collection_info(&app, &mintyplex_contract_with_collection).unwrap();
+            let cosmos_msg = mintyplex_contract_with_collection
+                .call_with_funds(
+                    ExecuteMsg::MintNFT(MintyplexContract::get_mock_mint_params(
+                        collection_info.collection_address.unwrap(),
+                        0,
+                    )),
+                    vec![coin(mint_fee.u128(), NATIVE_DENOM)],
+                )
+                .unwrap();
+            app.execute(Addr::unchecked(SHOPPER), cosmos_msg).unwrap();
+
+            // the platform share rounds down, the creator gets the dust
+            let creator_amount = get_creator_balance(&app, &mintyplex_contract_with_collection)
+                .unwrap()
+                .balances[0]
+                .amount;
+            let mintyplex_amount = app
+                .wrap()
+                .query_balance(mintyplex_contract_with_collection.addr(), NATIVE_DENOM)
+                .unwrap()
+                .amount
+                - creator_amount;
+            assert_eq!(mintyplex_amount, Uint128::new(24));
+            assert_eq!(creator_amount, Uint128::new(975));
+            assert_eq!(mintyplex_amount + creator_amount, mint_fee);
+        }
+
+        #[test]
+        fn test_revenue_split() {
+            let collaborator = Addr::unchecked(ADMIN2);
+
+            let (mut app, mintyplex_contract_with_collection) =
+                app_with_collection_params(|params| {
+                    params.prices[0].amount = Uint128::new(1003);
+                    params.revenue_split = Some(RevenueSplit {
+                        payees: vec![
+                            Payee {
+                                address: Addr::unchecked(CREATOR),
+                                weight: 3,
+                            },
+                            Payee {
+                                address: collaborator.clone(),
+                                weight: 1,
+                            },
+                        ],
+                        primary: Addr::unchecked(CREATOR),
+                    });
+                });
+
+            mint(
+                &mut app,
+                &mintyplex_contract_with_collection,
+                vec![coin(1003, NATIVE_DENOM)],
+            )
+            .unwrap();
+
+            // 1003 - 20 platform fee leaves 983, split 3:1 with the dust going to the primary
+            let balance_of = |address: &Addr| -> Uint128 {
+                let res: CreatorBalanceResponse = app
+                    .wrap()
+                    .query_wasm_smart(
+                        mintyplex_contract_with_collection.addr(),
+                        &QueryMsg::CreatorBalance {
+                            creator: address.clone(),
+                        },
+                    )
+                    .unwrap();
+                res.balances[0].amount
+            };
+            assert_eq!(balance_of(&Addr::unchecked(CREATOR)), Uint128::new(738));
+            assert_eq!(balance_of(&collaborator), Uint128::new(245));
+
+            // should fail when the primary payee isn't part of the split
+            let invalid_split = UpdateRevenueSplitParams {
+                collection_name: MOCK_COLLECTION_NAME.to_string(),
+                revenue_split: Some(RevenueSplit {
+                    payees: vec![Payee {
+                        address: Addr::unchecked(CREATOR),
+                        weight: 1,
+                    }],
+                    primary: collaborator,
+                }),
+            };
+            let cosmos_msg = mintyplex_contract_with_collection
+                .call(ExecuteMsg::UpdateRevenueSplit(invalid_split))
+                .unwrap();
+            let err = app
+                .execute(Addr::unchecked(CREATOR), cosmos_msg)
+                .unwrap_err();
+            assert_eq!(
+                err.downcast::<ContractError>().unwrap(),
+                ContractError::InvalidRevenueSplit {}
+            );
+
+            let remove_split = ExecuteMsg::UpdateRevenueSplit(UpdateRevenueSplitParams {
+                collection_name: MOCK_COLLECTION_NAME.to_string(),
+                revenue_split: None,
+            });
+            let cosmos_msg = mintyplex_contract_with_collection
+                .call(remove_split)
+                .unwrap();
+
+            // should fail with unauthorized address
+            assert!(app
+                .execute(Addr::unchecked(UNAUTHORIZED), cosmos_msg.clone())
+                .is_err());
+
+            app.execute(Addr::unchecked(CREATOR), cosmos_msg).unwrap();
+
+            let collection_info =
+                get_collection_info(&app, &mintyplex_contract_with_collection).unwrap();
+            assert_eq!(collection_info.revenue_split, None);
+        }
+
+        #[test]
+        fn test_referral_commission() {
+            let referrer = Addr::unchecked(ADMIN2);
+
+            let (mut app, mintyplex_contract_with_collection) =
+                app_with_collection_params(|params| {
+                    params.referral_rate = Some(Decimal::percent(10));
+                });
+
+            let collection_info =
+                get_collection_info(&app, &mintyplex_contract_with_collection).unwrap();
+            let mut mint_params = MintyplexContract::get_mock_mint_params(
+                collection_info.collection_address.unwrap(),
+                0,
+            );
+
+            // should fail when the shopper refers themselves
+            mint_params.referrer = Some(Addr::unchecked(SHOPPER));
+            let cosmos_msg = mintyplex_contract_with_collection
+                .call_with_funds(
+                    ExecuteMsg::MintNFT(mint_params.clone()),
+                    vec![coin(MOCK_MINT_FEE, NATIVE_DENOM)],
+                )
+                .unwrap();
+            let err = app
+                .execute(Addr::unchecked(SHOPPER), cosmos_msg)
+                .unwrap_err();
+            assert_eq!(
+                err.downcast::<ContractError>().unwrap(),
+                ContractError::InvalidReferrer {}
+            );
+
+            mint_params.referrer = Some(referrer.clone());
+            let cosmos_msg = mintyplex_contract_with_collection
+                .call_with_funds(
+                    ExecuteMsg::MintNFT(mint_params),
+                    vec![coin(MOCK_MINT_FEE, NATIVE_DENOM)],
+                )
+                .unwrap();
+            app.execute(Addr::unchecked(SHOPPER), cosmos_msg).unwrap();
+
+            let creator_share = Uint128::new(MOCK_MINT_FEE)
+                - Uint128::new(MOCK_MINT_FEE).mul_floor(MOCK_MINT_PERCENT);
+            let commission = creator_share.mul_floor(Decimal::percent(10));
+
+            let earnings: ReferralEarningsResponse = app
+                .wrap()
+                .query_wasm_smart(
+                    mintyplex_contract_with_collection.addr(),
+                    &QueryMsg::ReferralEarnings {
+                        referrer: referrer.clone(),
+                    },
+                )
+                .unwrap();
+            assert_eq!(
+                earnings.earnings,
+                vec![DenomBalance {
+                    denom: Denom::Native(NATIVE_DENOM.to_string()),
+                    amount: commission,
+                }]
+            );
+
+            // the commission comes out of the creator share
+            assert_eq!(
+                get_creator_balance(&app, &mintyplex_contract_with_collection)
+                    .unwrap()
+                    .balances[0]
+                    .amount,
+                creator_share - commission
+            );
+
+            // the referrer claims like any other payee
+            let cosmos_msg = mintyplex_contract_with_collection
+                .call(ExecuteMsg::ClaimEarnings { denoms: None })
+                .unwrap();
+            app.execute(referrer.clone(), cosmos_msg).unwrap();
+            assert_eq!(
+                app.wrap()
+                    .query_balance(referrer, NATIVE_DENOM)
+                    .unwrap()
+                    .amount,
+                commission
+            );
+
+            // should fail with a referral rate above 100%
+            let cosmos_msg = mintyplex_contract_with_collection
+                .call(ExecuteMsg::UpdateReferralRate(UpdateReferralRateParams {
+                    collection_name: MOCK_COLLECTION_NAME.to_string(),
+                    referral_rate: Decimal::percent(101),
+                }))
+                .unwrap();
+            assert!(app.execute(Addr::unchecked(CREATOR), cosmos_msg).is_err());
+        }
+
+        #[test]
+        fn test_coupons() {
+            let (mut app, mintyplex_contract_with_collection) = app_with_collection();
+
+            let code_hash = hex::encode(Sha256::digest(b"LAUNCH30"));
+            let expires = app.block_info().time.plus_seconds(3600);
+
+            let coupon_params = CouponParams {
+                collection_name: MOCK_COLLECTION_NAME.to_string(),
+                code_hash: code_hash.clone(),
+                discount: Discount::Percent(Decimal::percent(30)),
+                max_redemptions: 1,
+                expires,
+            };
+            let cosmos_msg = mintyplex_contract_with_collection
+                .call(ExecuteMsg::RegisterCoupon(coupon_params))
+                .unwrap();
+
+            // should fail with unauthorized address
+            assert!(app
+                .execute(Addr::unchecked(UNAUTHORIZED), cosmos_msg.clone())
+                .is_err());
+
+            app.execute(Addr::unchecked(CREATOR), cosmos_msg).unwrap();
+
+            let collection_info =
+                get_collection_info(&app, &mintyplex_contract_with_collection).unwrap();
+            let mut mint_params = MintyplexContract::get_mock_mint_params(
+                collection_info.collection_address.unwrap(),
+                0,
+            );
+
+            let mut mint_with_coupon = |app: &mut App, code: &str, quantity: u32| {
+                mint_params.coupon = Some(code.to_string());
+                mint_params.quantity = Some(quantity);
+                let cosmos_msg = mintyplex_contract_with_collection
+                    .call_with_funds(
+                        ExecuteMsg::MintNFT(mint_params.clone()),
+                        vec![coin(MOCK_MINT_FEE * u128::from(quantity), NATIVE_DENOM)],
+                    )
+                    .unwrap();
+                app.execute(Addr::unchecked(SHOPPER), cosmos_msg)
+            };
+
+            let err = mint_with_coupon(&mut app, "WRONG", 1).unwrap_err();
+            assert_eq!(
+                err.downcast::<ContractError>().unwrap(),
+                ContractError::CouponNotFound {}
+            );
+
+            // 30% off, the rest of the payment is refunded
+            mint_with_coupon(&mut app, "LAUNCH30", 1).unwrap();
+            let discounted_fee = Uint128::new(MOCK_MINT_FEE * 7 / 10);
+            assert_eq!(
+                app.wrap()
+                    .query_balance(Addr::unchecked(SHOPPER), NATIVE_DENOM)
+                    .unwrap()
+                    .amount,
+                Uint128::new(100000000) - discounted_fee
+            );
+
+            let coupon: Coupon = app
+                .wrap()
+                .query_wasm_smart(
+                    mintyplex_contract_with_collection.addr(),
+                    &QueryMsg::Coupon {
+                        creator: Addr::unchecked(CREATOR),
+                        collection_name: MOCK_COLLECTION_NAME.to_string(),
+                        code_hash,
+                    },
+                )
+                .unwrap();
+            assert_eq!(coupon.redemptions, 1);
+
+            let err = mint_with_coupon(&mut app, "LAUNCH30", 1).unwrap_err();
+            assert_eq!(
+                err.downcast::<ContractError>().unwrap(),
+                ContractError::CouponExhausted {}
+            );
+
+            let coupon_params = CouponParams {
+                collection_name: MOCK_COLLECTION_NAME.to_string(),
+                code_hash: hex::encode(Sha256::digest(b"EARLYBIRD")),
+                discount: Discount::Fixed {
+                    denom: Denom::Native(NATIVE_DENOM.to_string()),
+                    amount: Uint128::new(1000),
+                },
+                max_redemptions: 10,
+                expires,
+            };
+            let cosmos_msg = mintyplex_contract_with_collection
+                .call(ExecuteMsg::RegisterCoupon(coupon_params))
+                .unwrap();
+            app.execute(Addr::unchecked(CREATOR), cosmos_msg).unwrap();
+
+            // should fail when the coupon has fewer redemptions left than copies bought
+            let err = mint_with_coupon(&mut app, "EARLYBIRD", 11).unwrap_err();
+            assert_eq!(
+                err.downcast::<ContractError>().unwrap(),
+                ContractError::CouponExhausted {}
+            );
+
+            // fixed discounts come off every copy
+            mint_with_coupon(&mut app, "EARLYBIRD", 2).unwrap();
+            assert_eq!(
+                app.wrap()
+                    .query_balance(Addr::unchecked(SHOPPER), NATIVE_DENOM)
+                    .unwrap()
+                    .amount,
+                Uint128::new(100000000 - (MOCK_MINT_FEE - 1000) * 2) - discounted_fee
+            );
+
+            app.update_block(|block| block.time = expires);
+
+            let err = mint_with_coupon(&mut app, "EARLYBIRD", 1).unwrap_err();
+            assert_eq!(
+                err.downcast::<ContractError>().unwrap(),
+                ContractError::CouponExpired {}
+            );
+        }
+
+        #[test]
+        fn test_price_schedule() {
+            let (mut app, mintyplex_contract_with_collection) = app_with_collection();
+
+            let now = app.block_info().time;
+            let sale = ScheduledPrice {
+                start_time: now.plus_seconds(100),
+                end_time: Some(now.plus_seconds(200)),
+                prices: vec![Price {
+                    denom: Denom::Native(NATIVE_DENOM.to_string()),
+                    amount: Uint128::new(MOCK_MINT_FEE * 7 / 10),
+                }],
+            };
+
+            // should fail when a scheduled price ends before it starts
+            let cosmos_msg = mintyplex_contract_with_collection
+                .call(ExecuteMsg::SchedulePrices(SchedulePricesParams {
+                    collection_name: MOCK_COLLECTION_NAME.to_string(),
+                    price_schedule: vec![ScheduledPrice {
+                        end_time: Some(now),
+                        ..sale.clone()
+                    }],
+                }))
+                .unwrap();
+            assert!(app.execute(Addr::unchecked(CREATOR), cosmos_msg).is_err());
+
+            let cosmos_msg = mintyplex_contract_with_collection
+                .call(ExecuteMsg::SchedulePrices(SchedulePricesParams {
+                    collection_name: MOCK_COLLECTION_NAME.to_string(),
+                    price_schedule: vec![sale.clone()],
+                }))
+                .unwrap();
+
+            // should fail with unauthorized address
+            assert!(app
+                .execute(Addr::unchecked(UNAUTHORIZED), cosmos_msg.clone())
+                .is_err());
+
+            app.execute(Addr::unchecked(CREATOR), cosmos_msg).unwrap();
+
+            let query_schedule = |app: &App| -> PriceScheduleResponse {
+                app.wrap()
+                    .query_wasm_smart(
+                        mintyplex_contract_with_collection.addr(),
+                        &QueryMsg::PriceSchedule {
+                            creator: Addr::unchecked(CREATOR),
+                            collection_name: MOCK_COLLECTION_NAME.to_string(),
+                        },
+                    )
+                    .unwrap()
+            };
+
+            assert_eq!(
+                query_schedule(&app),
+                PriceScheduleResponse {
+                    current: MintyplexContract::get_mock_prices(),
+                    upcoming: vec![sale.clone()],
+                }
+            );
+
+            let underpay = vec![coin(MOCK_MINT_FEE * 7 / 10, NATIVE_DENOM)];
+
+            // the sale hasn't started yet
+            assert!(mint(
+                &mut app,
+                &mintyplex_contract_with_collection,
+                underpay.clone()
+            )
+            .is_err());
+
+            app.update_block(|block| block.time = now.plus_seconds(150));
+            assert_eq!(
+                query_schedule(&app),
+                PriceScheduleResponse {
+                    current: sale.prices.clone(),
+                    upcoming: vec![],
+                }
+            );
+            mint(
+                &mut app,
+                &mintyplex_contract_with_collection,
+                underpay.clone(),
+            )
+            .unwrap();
+
+            // back to the collection price once the sale ends
+            app.update_block(|block| block.time = now.plus_seconds(200));
+            assert_eq!(
+                query_schedule(&app).current,
+                MintyplexContract::get_mock_prices()
+            );
+            assert!(mint(&mut app, &mintyplex_contract_with_collection, underpay).is_err());
+        }
+
+        #[test]
+        fn test_dutch_auction() {
+            let auction = DutchAuction {
+                denom: Denom::Native(NATIVE_DENOM.to_string()),
+                start_price: Uint128::new(10000),
+                end_price: Uint128::new(1000),
+                start_time: mock_env().block.time.plus_seconds(100),
+                decay_interval: 1000,
+                steps: Some(4),
+            };
+
+            let (mut app, mintyplex_contract_with_collection) =
+                app_with_collection_params(|params| {
+                    params.pricing_mode = Some(PricingMode::DutchAuction(auction.clone()));
+                });
+
+            // should fail to create an auction whose price doesn't fall
+            let cw721_code_id = app.store_code(cw721_contract());
+            let mut collection_params =
+                MintyplexContract::get_mock_collection_params(cw721_code_id);
+            collection_params.name = "Rising".to_string();
+            collection_params.pricing_mode = Some(PricingMode::DutchAuction(DutchAuction {
+                end_price: auction.start_price,
+                ..auction.clone()
+            }));
+            let cosmos_msg = mintyplex_contract_with_collection
+                .call(ExecuteMsg::CreateCollection(collection_params))
+                .unwrap();
+            let err = app
+                .execute(Addr::unchecked(CREATOR), cosmos_msg)
+                .unwrap_err();
+            assert_eq!(
+                err.downcast::<ContractError>().unwrap(),
+                ContractError::InvalidAuction {}
+            );
+
+            // long auctions with many steps don't overflow
+            let long_auction = DutchAuction {
+                decay_interval: 20_000_000_000,
+                steps: Some(u32::MAX),
+                ..auction.clone()
+            };
+            assert_eq!(
+                long_auction.price_at(long_auction.start_time.plus_seconds(10_000_000_000)),
+                Uint128::new(5501)
+            );
+
+            let query_auction_price = |app: &App| -> AuctionPriceResponse {
+                app.wrap()
+                    .query_wasm_smart(
+                        mintyplex_contract_with_collection.addr(),
+                        &QueryMsg::AuctionPrice {
+                            creator: Addr::unchecked(CREATOR),
+                            collection_name: MOCK_COLLECTION_NAME.to_string(),
+                        },
+                    )
+                    .unwrap()
+            };
+
+            assert_eq!(
+                query_auction_price(&app),
+                AuctionPriceResponse {
+                    denom: auction.denom.clone(),
+                    price: auction.start_price,
+                    end_time: auction.start_time.plus_seconds(1000),
+                }
+            );
+
+            // should fail before the auction starts
+            let err = mint(
+                &mut app,
+                &mintyplex_contract_with_collection,
+                vec![coin(10000, NATIVE_DENOM)],
+            )
+            .unwrap_err();
+            assert_eq!(
+                err.downcast::<ContractError>().unwrap(),
+                ContractError::AuctionNotStarted {}
+            );
+
+            // one step of 2250 has been taken after 300 seconds
+            app.update_block(|block| block.time = auction.start_time.plus_seconds(300));
+            assert_eq!(query_auction_price(&app).price, Uint128::new(7750));
+
+            // should fail with a denom other than the auction denom
+            let err = mint(
+                &mut app,
+                &mintyplex_contract_with_collection,
+                vec![coin(10000, OTHER_DENOM)],
+            )
+            .unwrap_err();
+            assert_eq!(
+                err.downcast::<ContractError>().unwrap(),
+                ContractError::UnacceptedDenom {
+                    denom: OTHER_DENOM.to_string()
+                }
+            );
+
+            let shopper_balance = app.wrap().query_balance(SHOPPER, NATIVE_DENOM).unwrap();
+            mint(
+                &mut app,
+                &mintyplex_contract_with_collection,
+                vec![coin(10000, NATIVE_DENOM)],
+            )
+            .unwrap();
+
+            // the shopper is charged the current price and refunded the rest
+            assert_eq!(
+                app.wrap()
+                    .query_balance(SHOPPER, NATIVE_DENOM)
+                    .unwrap()
+                    .amount,
+                shopper_balance.amount - Uint128::new(7750)
+            );
+            assert_eq!(
+                get_platform_fees(&app, &mintyplex_contract_with_collection)
+                    .unwrap()
+                    .accrued,
+                Uint128::new(7750).mul_floor(MOCK_MINT_PERCENT)
+            );
+
+            // the price stays at the floor once the auction ends
+            app.update_block(|block| block.time = auction.start_time.plus_seconds(5000));
+            assert_eq!(query_auction_price(&app).price, auction.end_price);
+            mint(
+                &mut app,
+                &mintyplex_contract_with_collection,
+                vec![coin(1000, NATIVE_DENOM)],
+            )
+            .unwrap();
+        }
+
+        #[test]
+        fn test_quantity_tiers() {
+            let (mut app, mintyplex_contract_with_collection) =
+                app_with_collection_params(|params| {
+                    params.quantity_tiers = Some(vec![
+                        QuantityTier {
+                            min_quantity: 10,
+                            discount: Decimal::percent(20),
+                        },
+                        QuantityTier {
+                            min_quantity: 5,
+                            discount: Decimal::percent(10),
+                        },
+                    ]);
+                });
+
+            // should fail with a tier that starts from a single copy
+            let cw721_code_id = app.store_code(cw721_contract());
+            let mut collection_params =
+                MintyplexContract::get_mock_collection_params(cw721_code_id);
+            collection_params.name = "Single".to_string();
+            collection_params.quantity_tiers = Some(vec![QuantityTier {
+                min_quantity: 1,
+                discount: Decimal::percent(10),
+            }]);
+            let cosmos_msg = mintyplex_contract_with_collection
+                .call(ExecuteMsg::CreateCollection(collection_params))
+                .unwrap();
+            let err = app
+                .execute(Addr::unchecked(CREATOR), cosmos_msg)
+                .unwrap_err();
+            assert_eq!(
+                err.downcast::<ContractError>().unwrap(),
+                ContractError::InvalidQuantityTiers {}
+            );
+
+            let collection_info =
+                get_collection_info(&app, &mintyplex_contract_with_collection).unwrap();
+            let mut mint_params = MintyplexContract::get_mock_mint_params(
+                collection_info.collection_address.unwrap(),
+                0,
+            );
+
+            let mut mint_quantity = |app: &mut App, quantity: u32, amount: u128| {
+                mint_params.quantity = Some(quantity);
+                let cosmos_msg = mintyplex_contract_with_collection
+                    .call_with_funds(
+                        ExecuteMsg::MintNFT(mint_params.clone()),
+                        vec![coin(amount, NATIVE_DENOM)],
+                    )
+                    .unwrap();
+                app.execute(Addr::unchecked(SHOPPER), cosmos_msg)
+            };
+
+            // should fail with no copies or more than the cap
+            for quantity in [0, MAX_MINT_QUANTITY + 1] {
+                let err = mint_quantity(&mut app, quantity, MOCK_MINT_FEE * 100).unwrap_err();
+                assert_eq!(
+                    err.downcast::<ContractError>().unwrap(),
+                    ContractError::InvalidQuantity {
+                        max: MAX_MINT_QUANTITY
+                    }
+                );
+            }
+
+            // below the first tier the full unit price applies
+            mint_quantity(&mut app, 4, MOCK_MINT_FEE * 4).unwrap();
+            assert!(mint_quantity(&mut app, 4, MOCK_MINT_FEE * 4 - 1).is_err());
+
+            // 12 copies reach the 20% off tier
+            let total = MOCK_MINT_FEE * 12 * 8 / 10;
+            assert!(mint_quantity(&mut app, 12, total - 1).is_err());
+            mint_quantity(&mut app, 12, total).unwrap();
+
+            let token_index: u64 = app
+                .wrap()
+                .query_wasm_smart(
+                    mintyplex_contract_with_collection.addr(),
+                    &QueryMsg::TokenIndex {},
+                )
+                .unwrap();
+            assert_eq!(token_index, 16);
+
+            // the fee split is computed on the total
+            let mintyplex_amount = Uint128::new(MOCK_MINT_FEE * 4).mul_floor(MOCK_MINT_PERCENT)
+                + Uint128::new(total).mul_floor(MOCK_MINT_PERCENT);
+            assert_eq!(
+                get_platform_fees(&app, &mintyplex_contract_with_collection)
+                    .unwrap()
+                    .accrued,
+                mintyplex_amount
+            );
+            assert_eq!(
+                get_creator_balance(&app, &mintyplex_contract_with_collection)
+                    .unwrap()
+                    .balances[0]
+                    .amount,
+                Uint128::new(MOCK_MINT_FEE * 4 + total) - mintyplex_amount
+            );
+        }
+
+        #[test]
+        fn test_gift_mint() {
+            let (mut app, mintyplex_contract_with_collection) =
+                app_with_collection_params(|params| {
+                    params.gift_allowance = Some(2);
+                });
+
+            let gift = |recipients: Vec<&str>| {
+                mintyplex_contract_with_collection
+                    .call(ExecuteMsg::GiftMint(GiftMintParams {
+                        collection_name: MOCK_COLLECTION_NAME.to_string(),
+                        recipients: recipients.into_iter().map(String::from).collect(),
+                        token_uri: "gift".to_string(),
+                    }))
+                    .unwrap()
+            };
+
+            // should fail with unauthorized address
+            assert!(app
+                .execute(Addr::unchecked(UNAUTHORIZED), gift(vec![SHOPPER]))
+                .is_err());
+
+            // should fail with no recipients
+            assert!(app.execute(Addr::unchecked(CREATOR), gift(vec![])).is_err());
+
+            app.execute(Addr::unchecked(CREATOR), gift(vec![ADMIN, ADMIN2]))
+                .unwrap();
+
+            let collection_info =
+                get_collection_info(&app, &mintyplex_contract_with_collection).unwrap();
+            assert_eq!(collection_info.gifted, 2);
+
+            let token_index: u64 = app
+                .wrap()
+                .query_wasm_smart(
+                    mintyplex_contract_with_collection.addr(),
+                    &QueryMsg::TokenIndex {},
+                )
+                .unwrap();
+            assert_eq!(token_index, 2);
+
+            // gifts aren't sales
+            assert_eq!(
+                get_platform_fees(&app, &mintyplex_contract_with_collection)
+                    .unwrap()
+                    .accrued,
+                Uint128::zero()
+            );
+            assert!(
+                get_creator_balance(&app, &mintyplex_contract_with_collection)
+                    .unwrap()
+                    .balances
+                    .is_empty()
+            );
+
+            // should fail once the gift allowance is used up
+            let err = app
+                .execute(Addr::unchecked(CREATOR), gift(vec![SHOPPER]))
+                .unwrap_err();
+            assert_eq!(
+                err.downcast::<ContractError>().unwrap(),
+                ContractError::GiftAllowanceExceeded { remaining: 0 }
+            );
+        }
+
+        #[test]
+        fn test_refund_window() {
+            let (mut app, mintyplex_contract_with_collection) =
+                app_with_collection_params(|params| {
+                    params.refund_window = Some(100);
+                });
+
+            let collection_address = get_collection_info(&app, &mintyplex_contract_with_collection)
+                .unwrap()
+                .collection_address
+                .unwrap();
+
+            let request_refund = |token_id: &str| {
+                mintyplex_contract_with_collection
+                    .call(ExecuteMsg::RequestRefund {
+                        collection_address: collection_address.to_string(),
+                        token_id: token_id.to_string(),
+                    })
+                    .unwrap()
+            };
+            let query_escrow = |app: &App, token_id: &str| -> EscrowResponse {
+                app.wrap()
+                    .query_wasm_smart(
+                        mintyplex_contract_with_collection.addr(),
+                        &QueryMsg::Escrow {
+                            collection_address: collection_address.to_string(),
+                            token_id: token_id.to_string(),
+                        },
+                    )
+                    .unwrap()
+            };
+
+            let shopper_balance = app.wrap().query_balance(SHOPPER, NATIVE_DENOM).unwrap();
+
+            mint(
+                &mut app,
+                &mintyplex_contract_with_collection,
+                vec![coin(MOCK_MINT_FEE, NATIVE_DENOM)],
+            )
+            .unwrap();
+
+            // the sale is held in escrow
+            let escrow = query_escrow(&app, "1").escrow.unwrap();
+            assert_eq!(escrow.buyer, Addr::unchecked(SHOPPER));
+            assert_eq!(escrow.amount, Uint128::new(MOCK_MINT_FEE));
+            assert!(
+                get_creator_balance(&app, &mintyplex_contract_with_collection)
+                    .unwrap()
+                    .balances
+                    .is_empty()
+            );
+            assert!(claim_earnings(&mut app, &mintyplex_contract_with_collection).is_err());
+
+            // should fail with unauthorized address
+            let err = app
+                .execute(Addr::unchecked(UNAUTHORIZED), request_refund("1"))
+                .unwrap_err();
+            assert_eq!(
+                err.downcast::<ContractError>().unwrap(),
+                ContractError::Unauthorized {}
+            );
+
+            app.execute(Addr::unchecked(SHOPPER), request_refund("1"))
+                .unwrap();
+
+            assert_eq!(
+                app.wrap().query_balance(SHOPPER, NATIVE_DENOM).unwrap(),
+                shopper_balance
+            );
+            assert_eq!(
+                query_escrow(&app, "1"),
+                EscrowResponse {
+                    escrow: None,
+                    refunded_at: Some(app.block_info().time),
+                }
+            );
+
+            // should fail to refund the same token twice
+            let err = app
+                .execute(Addr::unchecked(SHOPPER), request_refund("1"))
+                .unwrap_err();
+            assert_eq!(
+                err.downcast::<ContractError>().unwrap(),
+                ContractError::RefundNotAvailable {}
+            );
+
+            mint(
+                &mut app,
+                &mintyplex_contract_with_collection,
+                vec![coin(MOCK_MINT_FEE, NATIVE_DENOM)],
+            )
+            .unwrap();
+
+            // should fail once the refund window closes
+            app.update_block(|block| block.time = block.time.plus_seconds(100));
+            let err = app
+                .execute(Addr::unchecked(SHOPPER), request_refund("2"))
+                .unwrap_err();
+            assert_eq!(
+                err.downcast::<ContractError>().unwrap(),
+                ContractError::RefundWindowClosed {}
+            );
+
+            // the escrow is released when the creator claims
+            let mintyplex_amount = Uint128::new(MOCK_MINT_FEE).mul_floor(MOCK_MINT_PERCENT);
+            claim_earnings(&mut app, &mintyplex_contract_with_collection).unwrap();
+            assert_eq!(
+                app.wrap()
+                    .query_balance(CREATOR, NATIVE_DENOM)
+                    .unwrap()
+                    .amount,
+                Uint128::new(MOCK_MINT_FEE) - mintyplex_amount
+            );
+            assert_eq!(
+                get_platform_fees(&app, &mintyplex_contract_with_collection)
+                    .unwrap()
+                    .accrued,
+                mintyplex_amount
+            );
+            assert_eq!(query_escrow(&app, "2").escrow, None);
+
+            // free sales can be refunded too
+            let cosmos_msg = mintyplex_contract_with_collection
+                .call(ExecuteMsg::RegisterCoupon(CouponParams {
+                    collection_name: MOCK_COLLECTION_NAME.to_string(),
+                    code_hash: hex::encode(Sha256::digest(b"FREE")),
+                    discount: Discount::Percent(Decimal::one()),
+                    max_redemptions: 1,
+                    expires: app.block_info().time.plus_seconds(3600),
+                }))
+                .unwrap();
+            app.execute(Addr::unchecked(CREATOR), cosmos_msg).unwrap();
+
+            let mut mint_params =
+                MintyplexContract::get_mock_mint_params(collection_address.clone(), 0);
+            mint_params.coupon = Some("FREE".to_string());
+            let cosmos_msg = mintyplex_contract_with_collection
+                .call_with_funds(
+                    ExecuteMsg::MintNFT(mint_params),
+                    vec![coin(MOCK_MINT_FEE, NATIVE_DENOM)],
+                )
+                .unwrap();
+            app.execute(Addr::unchecked(SHOPPER), cosmos_msg).unwrap();
+            assert!(query_escrow(&app, "3").escrow.unwrap().amount.is_zero());
+
+            app.execute(Addr::unchecked(SHOPPER), request_refund("3"))
+                .unwrap();
+            assert_eq!(
+                query_escrow(&app, "3").refunded_at,
+                Some(app.block_info().time)
+            );
+        }
+
+        #[test]
+        fn test_refund_burns_tokens() {
+            let (mut app, mintyplex_contract) = proper_instantiate();
+
+            let cw721_code_id = app.store_code(cw721_non_transferable_contract());
+            let collection_params = CollectionParams {
+                refund_window: Some(100),
+                ..MintyplexContract::get_mock_collection_params(cw721_code_id)
+            };
+            let cosmos_msg = mintyplex_contract
+                .call(ExecuteMsg::CreateCollection(collection_params))
+                .unwrap();
+            app.execute(Addr::unchecked(CREATOR), cosmos_msg).unwrap();
+
+            let collection_address = get_collection_info(&app, &mintyplex_contract)
+                .unwrap()
+                .collection_address
+                .unwrap();
+            let query_owner = |app: &App, token_id: &str| -> StdResult<OwnerOfResponse> {
+                app.wrap().query_wasm_smart(
+                    &collection_address,
+                    &cw721_non_transferable::QueryMsg::OwnerOf {
+                        token_id: token_id.to_string(),
+                        include_expired: None,
+                    },
+                )
+            };
+
+            for _ in 0..2 {
+                mint(
+                    &mut app,
+                    &mintyplex_contract,
+                    vec![coin(MOCK_MINT_FEE, NATIVE_DENOM)],
+                )
+                .unwrap();
+            }
+
+            // the factory holds the tokens while they can be refunded
+            assert_eq!(
+                query_owner(&app, "1").unwrap().owner,
+                mintyplex_contract.addr().to_string()
+            );
+
+            let cosmos_msg = mintyplex_contract
+                .call(ExecuteMsg::RequestRefund {
+                    collection_address: collection_address.to_string(),
+                    token_id: "1".to_string(),
+                })
+                .unwrap();
+            app.execute(Addr::unchecked(SHOPPER), cosmos_msg).unwrap();
+            assert!(query_owner(&app, "1").is_err());
+
+            // the buyer gets the token once the refund window closes
+            app.update_block(|block| block.time = block.time.plus_seconds(100));
+            let cosmos_msg = mintyplex_contract
+                .call(ExecuteMsg::ReleaseEscrow {
+                    creator: CREATOR.to_string(),
+                })
+                .unwrap();
+            app.execute(Addr::unchecked(UNAUTHORIZED), cosmos_msg)
+                .unwrap();
+            assert_eq!(query_owner(&app, "2").unwrap().owner, SHOPPER);
+        }
+
+        #[test]
+        fn test_subscriptions() {
+            let (mut app, mintyplex_contract_with_collection) =
+                app_with_collection_params(|params| {
+                    params.subscription_period = Some(1000);
+                });
+
+            let collection_address = get_collection_info(&app, &mintyplex_contract_with_collection)
+                .unwrap()
+                .collection_address
+                .unwrap();
+
+            let query_status = |app: &App| -> SubscriptionStatusResponse {
+                app.wrap()
+                    .query_wasm_smart(
+                        mintyplex_contract_with_collection.addr(),
+                        &QueryMsg::IsSubscriptionActive {
+                            owner: SHOPPER.to_string(),
+                            collection: collection_address.to_string(),
+                        },
+                    )
+                    .unwrap()
+            };
+            let renew = |app: &mut App, token_id: &str| {
+                let cosmos_msg = mintyplex_contract_with_collection
+                    .call_with_funds(
+                        ExecuteMsg::Renew(RenewParams {
+                            collection_creator: Addr::unchecked(CREATOR),
+                            collection_name: MOCK_COLLECTION_NAME.to_string(),
+                            token_id: token_id.to_string(),
+                        }),
+                        vec![coin(MOCK_MINT_FEE, NATIVE_DENOM)],
+                    )
+                    .unwrap();
+                app.execute(Addr::unchecked(SHOPPER), cosmos_msg)
+            };
+
+            assert_eq!(
+                query_status(&app),
+                SubscriptionStatusResponse {
+                    active: false,
+                    expires: None,
+                }
+            );
+
+            let minted_at = app.block_info().time;
+            mint(
+                &mut app,
+                &mintyplex_contract_with_collection,
+                vec![coin(MOCK_MINT_FEE, NATIVE_DENOM)],
+            )
+            .unwrap();
+
+            assert_eq!(
+                query_status(&app),
+                SubscriptionStatusResponse {
+                    active: true,
+                    expires: Some(minted_at.plus_seconds(1000)),
+                }
+            );
+
+            // the subscription lapses after one period
+            app.update_block(|block| block.time = minted_at.plus_seconds(1500));
+            assert!(!query_status(&app).active);
+
+            // should fail to renew a token that doesn't exist
+            let err = renew(&mut app, "2").unwrap_err();
+            assert_eq!(
+                err.downcast::<ContractError>().unwrap(),
+                ContractError::SubscriptionNotFound {}
+            );
+
+            // a lapsed subscription restarts from the renewal
+            renew(&mut app, "1").unwrap();
+            assert_eq!(
+                query_status(&app),
+                SubscriptionStatusResponse {
+                    active: true,
+                    expires: Some(minted_at.plus_seconds(2500)),
+                }
+            );
+
+            // an active subscription is extended from its expiry
+            renew(&mut app, "1").unwrap();
+            assert_eq!(
+                query_status(&app).expires,
+                Some(minted_at.plus_seconds(3500))
+            );
+
+            let creator_share = Uint128::new(MOCK_MINT_FEE)
+                - Uint128::new(MOCK_MINT_FEE).mul_floor(MOCK_MINT_PERCENT);
+            assert_eq!(
+                get_creator_balance(&app, &mintyplex_contract_with_collection)
+                    .unwrap()
+                    .balances[0]
+                    .amount,
+                creator_share * Uint128::new(3)
+            );
+        }
+
+        #[test]
+        fn test_refunded_subscription() {
+            let (mut app, mintyplex_contract_with_collection) =
+                app_with_collection_params(|params| {
+                    params.subscription_period = Some(1000);
+                    params.refund_window = Some(100);
+                });
+
+            let collection_address = get_collection_info(&app, &mintyplex_contract_with_collection)
+                .unwrap()
+                .collection_address
+                .unwrap();
+
+            let query_status = |app: &App| -> SubscriptionStatusResponse {
+                app.wrap()
+                    .query_wasm_smart(
+                        mintyplex_contract_with_collection.addr(),
+                        &QueryMsg::IsSubscriptionActive {
+                            owner: SHOPPER.to_string(),
+                            collection: collection_address.to_string(),
+                        },
+                    )
+                    .unwrap()
+            };
+            let request_refund = |app: &mut App, token_id: &str| {
+                let cosmos_msg = mintyplex_contract_with_collection
+                    .call(ExecuteMsg::RequestRefund {
+                        collection_address: collection_address.to_string(),
+                        token_id: token_id.to_string(),
+                    })
+                    .unwrap();
+                app.execute(Addr::unchecked(SHOPPER), cosmos_msg)
+            };
+
+            let minted_at = app.block_info().time;
+            mint(
+                &mut app,
+                &mintyplex_contract_with_collection,
+                vec![coin(MOCK_MINT_FEE, NATIVE_DENOM)],
+            )
+            .unwrap();
+
+            app.update_block(|block| block.time = minted_at.plus_seconds(50));
+            mint(
+                &mut app,
+                &mintyplex_contract_with_collection,
+                vec![coin(MOCK_MINT_FEE, NATIVE_DENOM)],
+            )
+            .unwrap();
+            assert_eq!(
+                query_status(&app).expires,
+                Some(minted_at.plus_seconds(1050))
+            );
+
+            // the expiry falls back to the tokens left
+            request_refund(&mut app, "2").unwrap();
+            assert_eq!(
+                query_status(&app),
+                SubscriptionStatusResponse {
+                    active: true,
+                    expires: Some(minted_at.plus_seconds(1000)),
+                }
+            );
+
+            request_refund(&mut app, "1").unwrap();
+            assert_eq!(
+                query_status(&app),
+                SubscriptionStatusResponse {
+                    active: false,
+                    expires: None,
+                }
+            );
+
+            // should fail to renew a refunded token
+            let cosmos_msg = mintyplex_contract_with_collection
+                .call_with_funds(
+                    ExecuteMsg::Renew(RenewParams {
+                        collection_creator: Addr::unchecked(CREATOR),
+                        collection_name: MOCK_COLLECTION_NAME.to_string(),
+                        token_id: "1".to_string(),
+                    }),
+                    vec![coin(MOCK_MINT_FEE, NATIVE_DENOM)],
+                )
+                .unwrap();
+            let err = app
+                .execute(Addr::unchecked(SHOPPER), cosmos_msg)
+                .unwrap_err();
+            assert_eq!(
+                err.downcast::<ContractError>().unwrap(),
+                ContractError::SubscriptionNotFound {}
+            );
+        }
+
+        #[test]
+        fn test_usd_pricing() {
+            let native = Denom::Native(NATIVE_DENOM.to_string());
+
+            // $5 in micro USD
+            let (mut app, mintyplex_contract_with_collection) =
+                app_with_collection_params(|params| {
+                    params.pricing_mode = Some(PricingMode::Usd(UsdPrice {
+                        amount: Uint128::new(5_000_000),
+                        denoms: vec![native.clone()],
+                    }));
+                });
+
+            let collection_info =
+                get_collection_info(&app, &mintyplex_contract_with_collection).unwrap();
+            let mint_params = MintyplexContract::get_mock_mint_params(
+                collection_info.collection_address.unwrap(),
+                0,
+            );
+
+            let mint_with_max = |app: &mut App, amount: u128, max_payment: Option<u128>| {
+                let cosmos_msg = mintyplex_contract_with_collection
+                    .call_with_funds(
+                        ExecuteMsg::MintNFT(MintParams {
+                            max_payment: max_payment.map(Uint128::new),
+                            ..mint_params.clone()
+                        }),
+                        vec![coin(amount, NATIVE_DENOM)],
+                    )
+                    .unwrap();
+                app.execute(Addr::unchecked(SHOPPER), cosmos_msg)
+            };
+
+            // should fail without a price oracle
+            let err = mint_with_max(&mut app, MOCK_MINT_FEE * 10, None).unwrap_err();
+            assert_eq!(
+                err.downcast::<ContractError>().unwrap(),
+                ContractError::OracleNotConfigured {}
+            );
+
+            let oracle_code_id = app.store_code(mock_oracle_contract());
+            let oracle = app
+                .instantiate_contract(
+                    oracle_code_id,
+                    Addr::unchecked(ADMIN),
+                    &Empty {},
+                    &[],
+                    "oracle",
+                    None,
+                )
+                .unwrap();
+            let set_price = |app: &mut App, price: Decimal| {
+                app.execute_contract(
+                    Addr::unchecked(ADMIN),
+                    oracle.clone(),
+                    &MockOracleExecuteMsg::SetPrice {
+                        denom: native.clone(),
+                        price,
+                    },
+                    &[],
+                )
+                .unwrap();
+            };
+
+            let cosmos_msg = mintyplex_contract_with_collection
+                .call(ExecuteMsg::UpdateConfig(Config {
+                    owner: Addr::unchecked(ADMIN),
+                    mint_percent: MOCK_MINT_PERCENT,
+                    denom: NATIVE_DENOM.to_string(),
+                    price_oracle: Some(PriceOracle {
+                        address: oracle.clone(),
+                        max_age: 60,
+                    }),
+                    charge_tip_fee: false,
+                    paused: false,
+                }))
+                .unwrap();
+            app.execute(Addr::unchecked(ADMIN), cosmos_msg).unwrap();
+
+            // $2.50 per xion makes the price 2 xion
+            set_price(&mut app, Decimal::percent(250));
+
+            // should fail when the price is above the max payment
+            let err = mint_with_max(&mut app, 2_000_000, Some(1_999_999)).unwrap_err();
+            assert_eq!(
+                err.downcast::<ContractError>().unwrap(),
+                ContractError::MaxPaymentExceeded {
+                    max_payment: Uint128::new(1_999_999),
+                    price: Uint128::new(2_000_000),
+                }
+            );
+
+            assert!(mint_with_max(&mut app, 1_999_999, None).is_err());
+            mint_with_max(&mut app, 2_000_000, Some(2_000_000)).unwrap();
+
+            // the converted price is rounded up
+            set_price(&mut app, Decimal::from_ratio(3u128, 1u128));
+            let shopper_balance = app.wrap().query_balance(SHOPPER, NATIVE_DENOM).unwrap();
+            mint_with_max(&mut app, 2_000_000, None).unwrap();
+            assert_eq!(
+                app.wrap()
+                    .query_balance(SHOPPER, NATIVE_DENOM)
+                    .unwrap()
+                    .amount,
+                shopper_balance.amount - Uint128::new(1_666_667)
+            );
+
+            // should fail once the oracle price goes stale
+            app.update_block(|block| block.time = block.time.plus_seconds(61));
+            let err = mint_with_max(&mut app, 2_000_000, None).unwrap_err();
+            assert_eq!(
+                err.downcast::<ContractError>().unwrap(),
+                ContractError::StaleOraclePrice { max_age: 60 }
+            );
+        }
+
+        #[test]
+        fn test_tips() {
+            let (mut app, mintyplex_contract_with_collection) = app_with_collection();
+
+            let tip = |app: &mut App, collection_name: &str, memo: Option<String>, amount: u128| {
+                let cosmos_msg = mintyplex_contract_with_collection
+                    .call_with_funds(
+                        ExecuteMsg::Tip {
+                            creator: CREATOR.to_string(),
+                            collection_name: collection_name.to_string(),
+                            memo,
+                        },
+                        vec![coin(amount, NATIVE_DENOM)],
+                    )
+                    .unwrap();
+                app.execute(Addr::unchecked(SHOPPER), cosmos_msg)
+            };
+            let query_tips = |app: &App, query_msg: QueryMsg| -> TipsResponse {
+                app.wrap()
+                    .query_wasm_smart(mintyplex_contract_with_collection.addr(), &query_msg)
+                    .unwrap()
+            };
+            let native_tips = |amount: u128| TipsResponse {
+                tips: vec![DenomBalance {
+                    denom: Denom::Native(NATIVE_DENOM.to_string()),
+                    amount: Uint128::new(amount),
+                }],
+            };
+
+            // should fail for a collection that doesn't exist
+            assert!(tip(&mut app, "missing", None, 500).is_err());
+
+            // should fail with a memo that is too long
+            assert!(tip(&mut app, MOCK_COLLECTION_NAME, Some("a".repeat(257)), 500).is_err());
+
+            tip(
+                &mut app,
+                MOCK_COLLECTION_NAME,
+                Some("love your work".to_string()),
+                500,
+            )
+            .unwrap();
+
+            // tips are free of platform fees by default
+            assert_eq!(
+                get_creator_balance(&app, &mintyplex_contract_with_collection)
+                    .unwrap()
+                    .balances[0]
+                    .amount,
+                Uint128::new(500)
+            );
+
+            let cosmos_msg = mintyplex_contract_with_collection
+                .call(ExecuteMsg::UpdateConfig(Config {
+                    owner: Addr::unchecked(ADMIN),
+                    mint_percent: MOCK_MINT_PERCENT,
+                    denom: NATIVE_DENOM.to_string(),
+                    price_oracle: None,
+                    charge_tip_fee: true,
+                    paused: false,
+                }))
+                .unwrap();
+            app.execute(Addr::unchecked(ADMIN), cosmos_msg).unwrap();
+
+            tip(&mut app, MOCK_COLLECTION_NAME, None, 1000).unwrap();
+
+            let mintyplex_amount = Uint128::new(1000).mul_floor(MOCK_MINT_PERCENT);
+            assert_eq!(
+                get_platform_fees(&app, &mintyplex_contract_with_collection)
+                    .unwrap()
+                    .accrued,
+                mintyplex_amount
+            );
+            assert_eq!(
+                get_creator_balance(&app, &mintyplex_contract_with_collection)
+                    .unwrap()
+                    .balances[0]
+                    .amount,
+                Uint128::new(1500) - mintyplex_amount
+            );
+
+            assert_eq!(
+                query_tips(
+                    &app,
+                    QueryMsg::CreatorTips {
+                        creator: Addr::unchecked(CREATOR),
+                    }
+                ),
+                native_tips(1500)
+            );
+            assert_eq!(
+                query_tips(
+                    &app,
+                    QueryMsg::CollectionTips {
+                        creator: Addr::unchecked(CREATOR),
+                        collection_name: MOCK_COLLECTION_NAME.to_string(),
+                    }
+                ),
+                native_tips(1500)
+            );
+        }
+
+        #[test]
+        fn test_fee_overrides() {
+            let (mut app, mintyplex_contract_with_collection) = app_with_collection();
+
+            let query_mint_percent = |app: &App| -> MintPercentResponse {
+                app.wrap()
+                    .query_wasm_smart(
+                        mintyplex_contract_with_collection.addr(),
+                        &QueryMsg::MintPercent {
+                            creator: Addr::unchecked(CREATOR),
+                            collection_name: MOCK_COLLECTION_NAME.to_string(),
+                        },
+                    )
+                    .unwrap()
+            };
+            let set_creator_fee = |mint_percent: Option<Decimal>| {
+                mintyplex_contract_with_collection
+                    .call(ExecuteMsg::SetCreatorFee(SetCreatorFeeParams {
+                        creator: CREATOR.to_string(),
+                        mint_percent,
+                    }))
+                    .unwrap()
+            };
+            let set_collection_fee = |mint_percent: Option<Decimal>| {
+                mintyplex_contract_with_collection
+                    .call(ExecuteMsg::SetCollectionFee(SetCollectionFeeParams {
+                        creator: CREATOR.to_string(),
+                        collection_name: MOCK_COLLECTION_NAME.to_string(),
+                        mint_percent,
+                    }))
+                    .unwrap()
+            };
+
+            assert_eq!(
+                query_mint_percent(&app),
+                MintPercentResponse {
+                    mint_percent: MOCK_MINT_PERCENT,
+                    source: FeeSource::Config,
+                }
+            );
+
+            // should fail with unauthorized address
+            let err = app
+                .execute(
+                    Addr::unchecked(CREATOR),
+                    set_creator_fee(Some(Decimal::zero())),
+                )
+                .unwrap_err();
+            assert_eq!(
+                err.downcast::<ContractError>().unwrap(),
+                ContractError::Unauthorized {}
+            );
+
+            // should fail above the platform fee cap
+            let err = app
+                .execute(
+                    Addr::unchecked(ADMIN),
+                    set_creator_fee(Some(MAX_MINT_PERCENT + Decimal::percent(1))),
+                )
+                .unwrap_err();
+            assert_eq!(
+                err.downcast::<ContractError>().unwrap(),
+                ContractError::MintPercentTooHigh {
+                    max: MAX_MINT_PERCENT
+                }
+            );
+
+            // partner rate for the creator
+            app.execute(
+                Addr::unchecked(ADMIN),
+                set_creator_fee(Some(Decimal::percent(1))),
+            )
+            .unwrap();
+            assert_eq!(
+                query_mint_percent(&app),
+                MintPercentResponse {
+                    mint_percent: Decimal::percent(1),
+                    source: FeeSource::Creator,
+                }
+            );
+
+            mint(
+                &mut app,
+                &mintyplex_contract_with_collection,
+                vec![coin(MOCK_MINT_FEE, NATIVE_DENOM)],
+            )
+            .unwrap();
+            let partner_fee = Uint128::new(MOCK_MINT_FEE).mul_floor(Decimal::percent(1));
+            assert_eq!(
+                get_platform_fees(&app, &mintyplex_contract_with_collection)
+                    .unwrap()
+                    .accrued,
+                partner_fee
+            );
+
+            // zero fee launch for the collection takes precedence
+            app.execute(
+                Addr::unchecked(ADMIN),
+                set_collection_fee(Some(Decimal::zero())),
+            )
+            .unwrap();
+            assert_eq!(
+                query_mint_percent(&app),
+                MintPercentResponse {
+                    mint_percent: Decimal::zero(),
+                    source: FeeSource::Collection,
+                }
+            );
+
+            mint(
+                &mut app,
+                &mintyplex_contract_with_collection,
+                vec![coin(MOCK_MINT_FEE, NATIVE_DENOM)],
+            )
+            .unwrap();
+            assert_eq!(
+                get_platform_fees(&app, &mintyplex_contract_with_collection)
+                    .unwrap()
+                    .accrued,
+                partner_fee
+            );
+
+            // removing the overrides falls back to the config rate
+            app.execute(Addr::unchecked(ADMIN), set_collection_fee(None))
+                .unwrap();
+            app.execute(Addr::unchecked(ADMIN), set_creator_fee(None))
+                .unwrap();
+            assert_eq!(query_mint_percent(&app).source, FeeSource::Config);
+        }
+
+        #[test]
+        fn test_mint_into_other_collection() {
+            let (mut app, mintyplex_contract_with_collection) =
+                app_with_collection_params(|params| {
+                    params.max_supply = Some(1);
+                });
+
+            let cw721_code_id = app.store_code(cw721_contract());
+            let mut collection_params =
+                MintyplexContract::get_mock_collection_params(cw721_code_id);
+            collection_params.name = "cheap product".to_string();
+            let cosmos_msg = mintyplex_contract_with_collection
+                .call(ExecuteMsg::CreateCollection(collection_params))
+                .unwrap();
+            app.execute(Addr::unchecked(CREATOR), cosmos_msg).unwrap();
+
+            mint(
+                &mut app,
+                &mintyplex_contract_with_collection,
+                vec![coin(MOCK_MINT_FEE, NATIVE_DENOM)],
+            )
+            .unwrap();
+
+            // should fail to mint into the sold out collection through another one
+            let collection_info =
+                get_collection_info(&app, &mintyplex_contract_with_collection).unwrap();
+            let mut mint_params = MintyplexContract::get_mock_mint_params(
+                collection_info.collection_address.unwrap(),
+                0,
+            );
+            mint_params.collection_name = "cheap product".to_string();
+            let cosmos_msg = mintyplex_contract_with_collection
+                .call_with_funds(
+                    ExecuteMsg::MintNFT(mint_params),
+                    vec![coin(MOCK_MINT_FEE, NATIVE_DENOM)],
+                )
+                .unwrap();
+            let err = app
+                .execute(Addr::unchecked(SHOPPER), cosmos_msg)
+                .unwrap_err();
+            assert_eq!(
+                err.downcast::<ContractError>().unwrap(),
+                ContractError::CollectionAddressMismatch {}
+            );
+        }
+
+        #[test]
+        fn test_max_supply() {
+            let (mut app, mintyplex_contract_with_collection) =
+                app_with_collection_params(|params| {
+                    params.max_supply = Some(3);
+                });
+
+            let collection_info =
+                get_collection_info(&app, &mintyplex_contract_with_collection).unwrap();
+            let mut mint_params = MintyplexContract::get_mock_mint_params(
+                collection_info.collection_address.unwrap(),
+                0,
+            );
+            mint_params.quantity = Some(2);
+            let mint_two = mintyplex_contract_with_collection
+                .call_with_funds(
+                    ExecuteMsg::MintNFT(mint_params),
+                    vec![coin(MOCK_MINT_FEE * 2, NATIVE_DENOM)],
+                )
+                .unwrap();
+            let update_max_supply = |max_supply: u64| {
+                mintyplex_contract_with_collection
+                    .call(ExecuteMsg::UpdateMaxSupply(UpdateMaxSupplyParams {
+                        collection_name: MOCK_COLLECTION_NAME.to_string(),
+                        max_supply,
+                    }))
+                    .unwrap()
+            };
+
+            app.execute(Addr::unchecked(SHOPPER), mint_two.clone())
+                .unwrap();
+
+            // should fail when the mint goes past the cap
+            let err = app.execute(Addr::unchecked(SHOPPER), mint_two).unwrap_err();
+            assert_eq!(
+                err.downcast::<ContractError>().unwrap(),
+                ContractError::SoldOut {}
+            );
+
+            // should fail to raise the cap above the announced supply or lower it below
+            // the copies already minted
+            for max_supply in [4, 1] {
+                let err = app
+                    .execute(Addr::unchecked(CREATOR), update_max_supply(max_supply))
+                    .unwrap_err();
+                assert_eq!(
+                    err.downcast::<ContractError>().unwrap(),
+                    ContractError::InvalidMaxSupply {}
+                );
+            }
+
+            app.execute(Addr::unchecked(CREATOR), update_max_supply(2))
+                .unwrap();
+
+            let collection_info =
+                get_collection_info(&app, &mintyplex_contract_with_collection).unwrap();
+            assert_eq!(collection_info.max_supply, Some(2));
+            assert_eq!(collection_info.announced_supply, Some(3));
+            assert_eq!(collection_info.minted, 2);
+
+            let err = mint(
+                &mut app,
+                &mintyplex_contract_with_collection,
+                vec![coin(MOCK_MINT_FEE, NATIVE_DENOM)],
+            )
+            .unwrap_err();
+            assert_eq!(
+                err.downcast::<ContractError>().unwrap(),
+                ContractError::SoldOut {}
+            );
+
+            // the cap can go back up to the announced supply
+            app.execute(Addr::unchecked(CREATOR), update_max_supply(3))
+                .unwrap();
+            mint(
+                &mut app,
+                &mintyplex_contract_with_collection,
+                vec![coin(MOCK_MINT_FEE, NATIVE_DENOM)],
+            )
+            .unwrap();
+        }
+
+        #[test]
+        fn test_close_collection() {
+            // closing locks both uncapped and capped collections
+            for max_supply in [None, Some(3)] {
+                let (mut app, mintyplex_contract_with_collection) =
+                    app_with_collection_params(|params| {
+                        params.max_supply = max_supply;
+                    });
+
+                mint(
+                    &mut app,
+                    &mintyplex_contract_with_collection,
+                    vec![coin(MOCK_MINT_FEE, NATIVE_DENOM)],
+                )
+                .unwrap();
+
+                let cosmos_msg = mintyplex_contract_with_collection
+                    .call(ExecuteMsg::CloseCollection {
+                        collection_name: MOCK_COLLECTION_NAME.to_string(),
+                    })
+                    .unwrap();
+
+                // should fail with unauthorized address
+                assert!(app
+                    .execute(Addr::unchecked(UNAUTHORIZED), cosmos_msg.clone())
+                    .is_err());
+
+                app.execute(Addr::unchecked(CREATOR), cosmos_msg).unwrap();
+
+                let collection_info =
+                    get_collection_info(&app, &mintyplex_contract_with_collection).unwrap();
+                assert_eq!(collection_info.max_supply, Some(1));
+                assert_eq!(collection_info.announced_supply, Some(1));
+
+                let err = mint(
+                    &mut app,
+                    &mintyplex_contract_with_collection,
+                    vec![coin(MOCK_MINT_FEE, NATIVE_DENOM)],
+                )
+                .unwrap_err();
+                assert_eq!(
+                    err.downcast::<ContractError>().unwrap(),
+                    ContractError::SoldOut {}
+                );
+
+                // a closed collection can't be reopened, not even up to its first cap
+                let cosmos_msg = mintyplex_contract_with_collection
+                    .call(ExecuteMsg::UpdateMaxSupply(UpdateMaxSupplyParams {
+                        collection_name: MOCK_COLLECTION_NAME.to_string(),
+                        max_supply: 3,
+                    }))
+                    .unwrap();
+                assert!(app.execute(Addr::unchecked(CREATOR), cosmos_msg).is_err());
+            }
+        }
+
+        #[test]
+        fn test_max_per_wallet() {
+            let (mut app, mintyplex_contract_with_collection) =
+                app_with_collection_params(|params| {
+                    params.max_per_wallet = Some(2);
+                });
+
+            let query_allowance = |app: &App| -> MintAllowanceResponse {
+                app.wrap()
+                    .query_wasm_smart(
+                        mintyplex_contract_with_collection.addr(),
+                        &QueryMsg::MintAllowance {
+                            creator: Addr::unchecked(CREATOR),
+                            collection_name: MOCK_COLLECTION_NAME.to_string(),
+                            buyer: Addr::unchecked(SHOPPER),
+                        },
+                    )
+                    .unwrap()
+            };
+
+            assert_eq!(
+                query_allowance(&app),
+                MintAllowanceResponse {
+                    purchased: 0,
+                    remaining: Some(2),
+                }
+            );
+
+            mint(
+                &mut app,
+                &mintyplex_contract_with_collection,
+                vec![coin(MOCK_MINT_FEE, NATIVE_DENOM)],
+            )
+            .unwrap();
+            assert_eq!(
+                query_allowance(&app),
+                MintAllowanceResponse {
+                    purchased: 1,
+                    remaining: Some(1),
+                }
+            );
+
+            // should fail when a purchase goes past the wallet limit
+            let collection_info =
+                get_collection_info(&app, &mintyplex_contract_with_collection).unwrap();
+            let mut mint_params = MintyplexContract::get_mock_mint_params(
+                collection_info.collection_address.unwrap(),
+                0,
+            );
+            mint_params.quantity = Some(2);
+            let cosmos_msg = mintyplex_contract_with_collection
+                .call_with_funds(
+                    ExecuteMsg::MintNFT(mint_params),
+                    vec![coin(MOCK_MINT_FEE * 2, NATIVE_DENOM)],
+                )
+                .unwrap();
+            let err = app
+                .execute(Addr::unchecked(SHOPPER), cosmos_msg)
+                .unwrap_err();
+            assert_eq!(
+                err.downcast::<ContractError>().unwrap(),
+                ContractError::WalletLimitReached { remaining: 1 }
+            );
+
+            mint(
+                &mut app,
+                &mintyplex_contract_with_collection,
+                vec![coin(MOCK_MINT_FEE, NATIVE_DENOM)],
+            )
+            .unwrap();
+            assert_eq!(query_allowance(&app).remaining, Some(0));
+
+            let err = mint(
+                &mut app,
+                &mintyplex_contract_with_collection,
+                vec![coin(MOCK_MINT_FEE, NATIVE_DENOM)],
+            )
+            .unwrap_err();
+            assert_eq!(
+                err.downcast::<ContractError>().unwrap(),
+                ContractError::WalletLimitReached { remaining: 0 }
+            );
+        }
+
+        #[test]
+        fn test_sale_phases() {
+            let now = mock_env().block.time;
+            let presale = SalePhase {
+                name: "presale".to_string(),
+                start_time: now.plus_seconds(100),
+                end_time: Some(now.plus_seconds(200)),
+                prices: vec![Price {
+                    denom: Denom::Native(NATIVE_DENOM.to_string()),
+                    amount: Uint128::new(MOCK_MINT_FEE / 2),
+                }],
+                max_per_wallet: Some(1),
+                merkle_root: None,
+            };
+            let public = SalePhase {
+                name: "public".to_string(),
+                start_time: now.plus_seconds(200),
+                end_time: None,
+                prices: vec![Price {
+                    denom: Denom::Native(NATIVE_DENOM.to_string()),
+                    amount: Uint128::new(MOCK_MINT_FEE),
+                }],
+                max_per_wallet: None,
+                merkle_root: None,
+            };
+
+            let (mut app, mintyplex_contract_with_collection) =
+                app_with_collection_params(|params| {
+                    params.phases = Some(vec![presale.clone(), public.clone()]);
+                });
+
+            let query_current_phase = |app: &App| -> CurrentPhaseResponse {
+                app.wrap()
+                    .query_wasm_smart(
+                        mintyplex_contract_with_collection.addr(),
+                        &QueryMsg::CurrentPhase {
+                            creator: Addr::unchecked(CREATOR),
+                            collection_name: MOCK_COLLECTION_NAME.to_string(),
+                        },
+                    )
+                    .unwrap()
+            };
+
+            // should fail before the first phase
+            assert_eq!(query_current_phase(&app).phase, None);
+            let err = mint(
+                &mut app,
+                &mintyplex_contract_with_collection,
+                vec![coin(MOCK_MINT_FEE, NATIVE_DENOM)],
+            )
+            .unwrap_err();
+            assert_eq!(
+                err.downcast::<ContractError>().unwrap(),
+                ContractError::SaleNotActive {}
+            );
+
+            app.update_block(|block| block.time = now.plus_seconds(150));
+            assert_eq!(query_current_phase(&app).phase, Some(presale.clone()));
+
+            // should charge the presale price
+            mint(
+                &mut app,
+                &mintyplex_contract_with_collection,
+                vec![coin(MOCK_MINT_FEE / 2, NATIVE_DENOM)],
+            )
+            .unwrap();
+
+            // should fail past the presale wallet limit
+            let err = mint(
+                &mut app,
+                &mintyplex_contract_with_collection,
+                vec![coin(MOCK_MINT_FEE / 2, NATIVE_DENOM)],
+            )
+            .unwrap_err();
+            assert_eq!(
+                err.downcast::<ContractError>().unwrap(),
+                ContractError::PhaseWalletLimitReached {
+                    phase: "presale".to_string(),
+                    remaining: 0,
+                }
+            );
+
+            // the public phase has no wallet limit
+            app.update_block(|block| block.time = now.plus_seconds(200));
+            assert_eq!(query_current_phase(&app).phase, Some(public.clone()));
+            for _ in 0..2 {
+                mint(
+                    &mut app,
+                    &mintyplex_contract_with_collection,
+                    vec![coin(MOCK_MINT_FEE, NATIVE_DENOM)],
+                )
+                .unwrap();
+            }
+
+            // should fail when phases overlap
+            let cosmos_msg = mintyplex_contract_with_collection
+                .call(ExecuteMsg::UpdatePhases(UpdatePhasesParams {
+                    collection_name: MOCK_COLLECTION_NAME.to_string(),
+                    phases: vec![
+                        presale.clone(),
+                        SalePhase {
+                            start_time: now.plus_seconds(150),
+                            ..public.clone()
+                        },
+                    ],
+                }))
+                .unwrap();
+            let err = app
+                .execute(Addr::unchecked(CREATOR), cosmos_msg)
+                .unwrap_err();
+            assert_eq!(
+                err.downcast::<ContractError>().unwrap(),
+                ContractError::InvalidSalePhases {}
+            );
+
+            // removing the phases opens mints at the collection price
+            let cosmos_msg = mintyplex_contract_with_collection
+                .call(ExecuteMsg::UpdatePhases(UpdatePhasesParams {
+                    collection_name: MOCK_COLLECTION_NAME.to_string(),
+                    phases: vec![],
+                }))
+                .unwrap();
+            app.execute(Addr::unchecked(CREATOR), cosmos_msg).unwrap();
+            assert_eq!(query_current_phase(&app).phase, None);
+            mint(
+                &mut app,
+                &mintyplex_contract_with_collection,
+                vec![coin(MOCK_MINT_FEE, NATIVE_DENOM)],
+            )
+            .unwrap();
+        }
+
+        #[test]
+        fn test_merkle_allowlist() {
+            let shopper_leaf: [u8; 32] = Sha256::digest(format!("{SHOPPER}:2").as_bytes()).into();
+            let other_leaf: [u8; 32] = Sha256::digest(ADMIN2.as_bytes()).into();
+            let (first, second) = if shopper_leaf <= other_leaf {
+                (shopper_leaf, other_leaf)
+            } else {
+                (other_leaf, shopper_leaf)
+            };
+            let merkle_root = hex::encode(
+                Sha256::new()
+                    .chain_update(first)
+                    .chain_update(second)
+                    .finalize(),
+            );
+
+            let (mut app, mintyplex_contract_with_collection) =
+                app_with_collection_params(|params| {
+                    params.merkle_root = Some(merkle_root.clone());
+                });
+
+            let collection_info =
+                get_collection_info(&app, &mintyplex_contract_with_collection).unwrap();
+            assert_eq!(collection_info.merkle_root, Some(merkle_root));
+
+            let mint_with_proof = |app: &mut App, allocation: Option<u32>, quantity: u32| {
+                let mut mint_params = MintyplexContract::get_mock_mint_params(
+                    collection_info.collection_address.clone().unwrap(),
+                    0,
+                );
+                mint_params.merkle_proof = Some(vec![hex::encode(other_leaf)]);
+                mint_params.allocation = allocation;
+                mint_params.quantity = Some(quantity);
+
+                let cosmos_msg = mintyplex_contract_with_collection
+                    .call_with_funds(
+                        ExecuteMsg::MintNFT(mint_params),
+                        vec![coin(MOCK_MINT_FEE * u128::from(quantity), NATIVE_DENOM)],
+                    )
+                    .unwrap();
+                app.execute(Addr::unchecked(SHOPPER), cosmos_msg)
+            };
+
+            // should fail without a proof
+            let err = mint(
+                &mut app,
+                &mintyplex_contract_with_collection,
+                vec![coin(MOCK_MINT_FEE, NATIVE_DENOM)],
+            )
+            .unwrap_err();
+            assert_eq!(
+                err.downcast::<ContractError>().unwrap(),
+                ContractError::NotAllowlisted {}
+            );
+
+            // should fail when claiming a larger allocation
+            let err = mint_with_proof(&mut app, Some(3), 1).unwrap_err();
+            assert_eq!(
+                err.downcast::<ContractError>().unwrap(),
+                ContractError::NotAllowlisted {}
+            );
+
+            mint_with_proof(&mut app, Some(2), 1).unwrap();
+
+            // should fail past the allocation
+            let err = mint_with_proof(&mut app, Some(2), 2).unwrap_err();
+            assert_eq!(
+                err.downcast::<ContractError>().unwrap(),
+                ContractError::AllocationExceeded { remaining: 1 }
+            );
+
+            // should fail when updating a phase the collection doesn't have
+            let cosmos_msg = mintyplex_contract_with_collection
+                .call(ExecuteMsg::UpdateMerkleRoot(UpdateMerkleRootParams {
+                    collection_name: MOCK_COLLECTION_NAME.to_string(),
+                    phase_name: Some("presale".to_string()),
+                    merkle_root: None,
+                }))
+                .unwrap();
+            let err = app
+                .execute(Addr::unchecked(CREATOR), cosmos_msg)
+                .unwrap_err();
+            assert_eq!(
+                err.downcast::<ContractError>().unwrap(),
+                ContractError::PhaseNotFound {}
+            );
+
+            // should fail when the root isn't a sha256 digest
+            let cosmos_msg = mintyplex_contract_with_collection
+                .call(ExecuteMsg::UpdateMerkleRoot(UpdateMerkleRootParams {
+                    collection_name: MOCK_COLLECTION_NAME.to_string(),
+                    phase_name: None,
+                    merkle_root: Some("root".to_string()),
+                }))
+                .unwrap();
+            let err = app
+                .execute(Addr::unchecked(CREATOR), cosmos_msg)
+                .unwrap_err();
+            assert_eq!(
+                err.downcast::<ContractError>().unwrap(),
+                ContractError::InvalidMerkleRoot {}
+            );
+
+            // removing the allowlist opens mints to anyone
+            let cosmos_msg = mintyplex_contract_with_collection
+                .call(ExecuteMsg::UpdateMerkleRoot(UpdateMerkleRootParams {
+                    collection_name: MOCK_COLLECTION_NAME.to_string(),
+                    phase_name: None,
+                    merkle_root: None,
+                }))
+                .unwrap();
+            app.execute(Addr::unchecked(CREATOR), cosmos_msg).unwrap();
+            mint(
+                &mut app,
+                &mintyplex_contract_with_collection,
+                vec![coin(MOCK_MINT_FEE, NATIVE_DENOM)],
+            )
+            .unwrap();
+        }
+
+        #[test]
+        fn test_pause_collection() {
+            let (mut app, mintyplex_contract_with_collection) =
+                app_with_collection_params(|params| {
+                    params.subscription_period = Some(1000);
+                });
+
+            mint(
+                &mut app,
+                &mintyplex_contract_with_collection,
+                vec![coin(MOCK_MINT_FEE, NATIVE_DENOM)],
+            )
+            .unwrap();
+
+            let pause_msg = ExecuteMsg::PauseCollection {
+                collection_name: MOCK_COLLECTION_NAME.to_string(),
+            };
+
+            // should fail when pausing someone else's collection
+            let cosmos_msg = mintyplex_contract_with_collection
+                .call(pause_msg.clone())
+                .unwrap();
+            assert!(app
+                .execute(Addr::unchecked(UNAUTHORIZED), cosmos_msg)
+                .is_err());
+
+            let cosmos_msg = mintyplex_contract_with_collection.call(pause_msg).unwrap();
+            app.execute(Addr::unchecked(CREATOR), cosmos_msg).unwrap();
+            assert!(
+                get_collection_info(&app, &mintyplex_contract_with_collection)
+                    .unwrap()
+                    .paused
+            );
+
+            // should fail while the collection is paused
+            let err = mint(
+                &mut app,
+                &mintyplex_contract_with_collection,
+                vec![coin(MOCK_MINT_FEE, NATIVE_DENOM)],
+            )
+            .unwrap_err();
+            assert_eq!(
+                err.downcast::<ContractError>().unwrap(),
+                ContractError::CollectionPaused {}
+            );
+
+            // should fail to renew while the collection is paused
+            let cosmos_msg = mintyplex_contract_with_collection
+                .call_with_funds(
+                    ExecuteMsg::Renew(RenewParams {
+                        collection_creator: Addr::unchecked(CREATOR),
+                        collection_name: MOCK_COLLECTION_NAME.to_string(),
+                        token_id: "1".to_string(),
+                    }),
+                    vec![coin(MOCK_MINT_FEE, NATIVE_DENOM)],
+                )
+                .unwrap();
+            let err = app
+                .execute(Addr::unchecked(SHOPPER), cosmos_msg)
+                .unwrap_err();
+            assert_eq!(
+                err.downcast::<ContractError>().unwrap(),
+                ContractError::CollectionPaused {}
+            );
+
+            let cosmos_msg = mintyplex_contract_with_collection
+                .call(ExecuteMsg::ResumeCollection {
+                    collection_name: MOCK_COLLECTION_NAME.to_string(),
+                })
+                .unwrap();
+            app.execute(Addr::unchecked(CREATOR), cosmos_msg).unwrap();
+            assert!(
+                !get_collection_info(&app, &mintyplex_contract_with_collection)
+                    .unwrap()
+                    .paused
+            );
+
+            mint(
+                &mut app,
+                &mintyplex_contract_with_collection,
+                vec![coin(MOCK_MINT_FEE, NATIVE_DENOM)],
+            )
+            .unwrap();
+        }
+
+        #[test]
+        fn test_pause_platform() {
+            let (mut app, mintyplex_contract_with_collection) = app_with_collection();
+
+            mint(
+                &mut app,
+                &mintyplex_contract_with_collection,
+                vec![coin(MOCK_MINT_FEE, NATIVE_DENOM)],
+            )
+            .unwrap();
+
+            // should fail when the sender isn't the owner
+            let cosmos_msg = mintyplex_contract_with_collection
+                .call(ExecuteMsg::PausePlatform {})
+                .unwrap();
+            let err = app
+                .execute(Addr::unchecked(CREATOR), cosmos_msg.clone())
+                .unwrap_err();
+            assert_eq!(
+                err.downcast::<ContractError>().unwrap(),
+                ContractError::Unauthorized {}
+            );
+
+            app.execute(Addr::unchecked(ADMIN), cosmos_msg).unwrap();
+            let query_config = |app: &App| -> Config {
+                app.wrap()
+                    .query_wasm_smart(
+                        mintyplex_contract_with_collection.addr(),
+                        &QueryMsg::Config {},
+                    )
+                    .unwrap()
+            };
+            let config = query_config(&app);
+            assert!(config.paused);
+            assert!(
+                get_collection_info(&app, &mintyplex_contract_with_collection)
+                    .unwrap()
+                    .paused
+            );
+
+            // updating the config doesn't resume the platform
+            let cosmos_msg = mintyplex_contract_with_collection
+                .call(ExecuteMsg::UpdateConfig(Config {
+                    paused: false,
+                    ..config
+                }))
+                .unwrap();
+            app.execute(Addr::unchecked(ADMIN), cosmos_msg).unwrap();
+            assert!(query_config(&app).paused);
+
+            // should fail to mint and create collections while paused
+            let err = mint(
+                &mut app,
+                &mintyplex_contract_with_collection,
+                vec![coin(MOCK_MINT_FEE, NATIVE_DENOM)],
+            )
+            .unwrap_err();
+            assert_eq!(
+                err.downcast::<ContractError>().unwrap(),
+                ContractError::PlatformPaused {}
+            );
+
+            let cw721_code_id = app.store_code(cw721_contract());
+            let mut collection_params =
+                MintyplexContract::get_mock_collection_params(cw721_code_id);
+            collection_params.name = "other product".to_string();
+            let cosmos_msg = mintyplex_contract_with_collection
+                .call(ExecuteMsg::CreateCollection(collection_params))
+                .unwrap();
+            let err = app
+                .execute(Addr::unchecked(CREATOR), cosmos_msg)
+                .unwrap_err();
+            assert_eq!(
+                err.downcast::<ContractError>().unwrap(),
+                ContractError::PlatformPaused {}
+            );
+
+            // earnings can still be claimed and platform fees withdrawn
+            claim_earnings(&mut app, &mintyplex_contract_with_collection).unwrap();
+
+            let cosmos_msg = mintyplex_contract_with_collection
+                .call(ExecuteMsg::Withdraw(WithdrawParams {
+                    withdraw_amount: 0,
+                    withdraw_address: Addr::unchecked(ADMIN),
+                    coins: None,
+                    cw20_coins: None,
+                    withdraw_all: Some(true),
+                }))
+                .unwrap();
+            app.execute(Addr::unchecked(ADMIN), cosmos_msg).unwrap();
+            assert_eq!(
+                app.wrap()
+                    .query_balance(Addr::unchecked(ADMIN), NATIVE_DENOM)
+                    .unwrap()
+                    .amount,
+                Uint128::new(MOCK_MINT_FEE).mul_floor(MOCK_MINT_PERCENT)
+            );
+
+            let cosmos_msg = mintyplex_contract_with_collection
+                .call(ExecuteMsg::ResumePlatform {})
+                .unwrap();
+            app.execute(Addr::unchecked(ADMIN), cosmos_msg).unwrap();
+
+            mint(
+                &mut app,
+                &mintyplex_contract_with_collection,
+                vec![coin(MOCK_MINT_FEE, NATIVE_DENOM)],
+            )
+            .unwrap();
+        }
+
+        #[test]
+        fn test_update_mint_fee() {
+            let (mut app, mintyplex_contract_with_collection) = app_with_collection();
+
+            let collection_info =
+                get_collection_info(&app, &mintyplex_contract_with_collection).unwrap();
+
+            assert_eq!(collection_info.prices, MintyplexContract::get_mock_prices());
+
+            let new_prices = vec![
+                Price {
+                    denom: Denom::Native(NATIVE_DENOM.to_string()),
+                    amount: Uint128::new(10),
+                },
+                Price {
+                    denom: Denom::Native(OTHER_DENOM.to_string()),
+                    amount: Uint128::new(5),
+                },
+            ];
+
+            // should fail when a denom is listed twice
+            let duplicate_prices = vec![new_prices[0].clone(), new_prices[0].clone()];
+            let cosmos_msg = mintyplex_contract_with_collection
+                .call(ExecuteMsg::UpdateMintFee(UpdateMintFeeParams {
+                    collection_name: MOCK_COLLECTION_NAME.to_string(),
+                    prices: duplicate_prices,
+                }))
+                .unwrap();
+            assert!(app.execute(Addr::unchecked(CREATOR), cosmos_msg).is_err());
 
             let update_mint_fee_params = UpdateMintFeeParams {
                 collection_name: MOCK_COLLECTION_NAME.to_string(),
-                mint_fee: new_mint_fee,
+                prices: new_prices.clone(),
             };
 
             let msg = ExecuteMsg::UpdateMintFee(update_mint_fee_params);
@@ -313,7 +3376,7 @@ mod tests {
             let collection_info =
                 get_collection_info(&app, &mintyplex_contract_with_collection).unwrap();
 
-            assert_eq!(collection_info.mint_fee, new_mint_fee);
+            assert_eq!(collection_info.prices, new_prices);
         }
     }
 }
@@ -0,0 +1,53 @@
+use crate::msg::{OraclePriceResponse, OracleQueryMsg};
+use crate::state::denom_key;
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{
+    to_json_binary, Binary, Decimal, Deps, DepsMut, Empty, Env, MessageInfo, Response, StdResult,
+};
+use cw20::Denom;
+use cw_multi_test::{Contract, ContractWrapper};
+use cw_storage_plus::Map;
+
+// Mock price oracle
+#[cw_serde]
+pub enum MockOracleExecuteMsg {
+    /// Sets the price of a denom as of the current block time
+    SetPrice { denom: Denom, price: Decimal },
+}
+
+const PRICES: Map<&str, OraclePriceResponse> = Map::new("prices");
+
+fn execute(
+    deps: DepsMut,
+    env: Env,
+    _info: MessageInfo,
+    msg: MockOracleExecuteMsg,
+) -> StdResult<Response> {
+    match msg {
+        MockOracleExecuteMsg::SetPrice { denom, price } => {
+            let price = OraclePriceResponse {
+                price,
+                updated_at: env.block.time,
+            };
+            PRICES.save(deps.storage, &denom_key(&denom), &price)?;
+            Ok(Response::default())
+        }
+    }
+}
+
+fn query(deps: Deps, _env: Env, msg: OracleQueryMsg) -> StdResult<Binary> {
+    match msg {
+        OracleQueryMsg::Price { denom } => {
+            to_json_binary(&PRICES.load(deps.storage, &denom_key(&denom))?)
+        }
+    }
+}
+
+pub fn mock_oracle_contract() -> Box<dyn Contract<Empty>> {
+    let contract = ContractWrapper::new(
+        execute,
+        |_, _, _, _: Empty| -> StdResult<Response> { Ok(Response::default()) },
+        query,
+    );
+    Box::new(contract)
+}
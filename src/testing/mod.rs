@@ -4,3 +4,6 @@ mod integration_tests;
 pub mod constants;
 
 pub mod types;
+
+#[cfg(test)]
+pub mod mock_oracle;
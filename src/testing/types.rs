@@ -1,3 +1,4 @@
+#![allow(dead_code)]
 use serde::{Deserialize, Serialize};
 
 // Mock cw721 Contract
@@ -15,6 +16,10 @@ pub enum Cw721MockExecuteMsg {
         /// Metadata JSON Schema
         token_uri: Option<String>,
     },
+    /// Transfer a token to another account
+    TransferNft { recipient: String, token_id: String },
+    /// Burn a token
+    Burn { token_id: String },
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]